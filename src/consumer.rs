@@ -0,0 +1,229 @@
+use pinocchio::{account_info::{AccountInfo, Ref}, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}};
+
+use crate::{errors::PriceError, instructions::{get_price::PriceData, get_twap::TwapData}, states::{PriceAccount, PriceHistory, PriceStatus, ZeroCopy}};
+
+/// Reads a feed account passed directly to a consuming program, applying the same checks as
+/// `GetPrice`: owner, header, PDA and staleness. `max_staleness_secs` can only tighten the
+/// feed's configured bound. Unlike `GetPrice`, fails with `PriceNotTrading` unless the feed
+/// is `Trading`. Build the consuming program with the `no-entrypoint` feature.
+pub fn get_price_no_older_than(
+    price_account: &AccountInfo,
+    price_account_id: u64,
+    max_staleness_secs: Option<u64>
+) -> Result<PriceData, ProgramError> {
+    let price_account_data = PriceAccount::from_account_info(price_account)?;
+    price_account_data.verify_address(price_account.key(), price_account_id)?;
+
+    price_no_older_than(&price_account_data, Clock::get()?.unix_timestamp, max_staleness_secs)
+}
+
+/// Borrows the history of the feed at `price_account` after checking owner, header and that
+/// it belongs to the feed. Use `PriceHistory::iter` to walk the entries oldest first.
+pub fn get_price_history<'a>(
+    price_history: &'a AccountInfo,
+    price_account: &AccountInfo
+) -> Result<Ref<'a, PriceHistory>, ProgramError> {
+    let price_history_bump = PriceAccount::from_account_info(price_account)?.price_history_bump;
+
+    let price_history_data = PriceHistory::from_account_info(price_history)?;
+    price_history_data.verify_feed(price_account.key(), price_history_bump)?;
+
+    Ok(price_history_data)
+}
+
+/// Time-weighted average over the last `window_secs` seconds, with the same checks as
+/// `GetTwap` plus the `Trading` requirement. Resists single-block manipulation better than
+/// the spot price.
+pub fn get_twap(
+    price_account: &AccountInfo,
+    price_history: &AccountInfo,
+    price_account_id: u64,
+    window_secs: u64
+) -> Result<TwapData, ProgramError> {
+    let price_account_data = PriceAccount::from_account_info(price_account)?;
+    price_account_data.verify_address(price_account.key(), price_account_id)?;
+
+    let price_history_data = get_price_history(price_history, price_account)?;
+
+    twap(&price_account_data, &price_history_data, Clock::get()?.unix_timestamp, window_secs)
+}
+
+fn price_no_older_than(
+    price_account: &PriceAccount,
+    unix_timestamp: i64,
+    max_staleness_secs: Option<u64>
+) -> Result<PriceData, ProgramError> {
+    check_trading(price_account)?;
+    price_account.check_staleness(unix_timestamp, max_staleness_secs)?;
+
+    Ok(PriceData::from(price_account))
+}
+
+fn twap(
+    price_account: &PriceAccount,
+    price_history: &PriceHistory,
+    unix_timestamp: i64,
+    window_secs: u64
+) -> Result<TwapData, ProgramError> {
+    check_trading(price_account)?;
+    price_account.check_staleness(unix_timestamp, None)?;
+
+    let (price, confidence) = price_history.twap(unix_timestamp, window_secs)?;
+
+    Ok(TwapData {
+        price,
+        confidence,
+        exponent: price_account.exponent,
+        window_secs,
+        status: price_account.status,
+    })
+}
+
+fn check_trading(price_account: &PriceAccount) -> Result<(), ProgramError> {
+    if price_account.status()? != PriceStatus::Trading {
+        return Err(PriceError::PriceNotTrading.into());
+    };
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pinocchio::program_error::ProgramError;
+
+    use super::{price_no_older_than, twap};
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceHistory, PriceHistoryEntry, PriceStatus},
+    };
+
+    const NOW: i64 = 1_000;
+
+    fn trading_price_account(exponent: i32) -> PriceAccount {
+        PriceAccount {
+            price: 12_345,
+            confidence: 7,
+            exponent,
+            status: PriceStatus::Trading as u8,
+            last_updated_timestamp: NOW - 30,
+            max_staleness_secs: 60,
+            ..Default::default()
+        }
+    }
+
+    fn price_history(prices: &[(i64, i64)]) -> PriceHistory {
+        let mut price_history = PriceHistory::default();
+
+        for (slot, (timestamp, price)) in prices.iter().enumerate() {
+            price_history.append(PriceHistoryEntry {
+                price: *price,
+                confidence: 2,
+                timestamp: *timestamp,
+                slot: slot as u64,
+            });
+        }
+
+        price_history
+    }
+
+    #[test]
+    fn test_price_no_older_than_fresh() {
+        let price_account = trading_price_account(-8);
+
+        let price_data = price_no_older_than(&price_account, NOW, None).unwrap();
+        assert_eq!(price_data.price, 12_345);
+        assert_eq!(price_data.confidence, 7);
+        assert_eq!(price_data.status, PriceStatus::Trading as u8);
+        assert_eq!(price_data.last_updated_timestamp, NOW - 30);
+    }
+
+    #[test]
+    fn test_price_no_older_than_stale() {
+        let price_account = trading_price_account(-8);
+
+        assert!(price_no_older_than(&price_account, NOW + 30, None).is_ok());
+        assert_eq!(
+            price_no_older_than(&price_account, NOW + 31, None),
+            Err(ProgramError::Custom(PriceError::StalePrice as u32))
+        );
+    }
+
+    #[test]
+    fn test_price_no_older_than_override_only_tightens() {
+        let price_account = trading_price_account(-8);
+
+        assert_eq!(
+            price_no_older_than(&price_account, NOW, Some(29)),
+            Err(ProgramError::Custom(PriceError::StalePrice as u32))
+        );
+        assert!(price_no_older_than(&price_account, NOW, Some(30)).is_ok());
+        assert_eq!(
+            price_no_older_than(&price_account, NOW + 31, Some(3_600)),
+            Err(ProgramError::Custom(PriceError::StalePrice as u32))
+        );
+    }
+
+    #[test]
+    fn test_price_no_older_than_not_trading() {
+        for status in [PriceStatus::Halted, PriceStatus::Unknown] {
+            let price_account = PriceAccount {
+                status: status as u8,
+                ..trading_price_account(-8)
+            };
+
+            assert_eq!(
+                price_no_older_than(&price_account, NOW, None),
+                Err(ProgramError::Custom(PriceError::PriceNotTrading as u32))
+            );
+        }
+    }
+
+    #[test]
+    fn test_price_no_older_than_exponent() {
+        for exponent in [-8, 0, 3] {
+            let price_data = price_no_older_than(&trading_price_account(exponent), NOW, None).unwrap();
+
+            assert_eq!(price_data.exponent, exponent);
+            assert_eq!(price_data.price, 12_345);
+        }
+    }
+
+    #[test]
+    fn test_twap() {
+        let price_history = price_history(&[(NOW - 100, 100), (NOW - 50, 200)]);
+
+        let twap_data = twap(&trading_price_account(-6), &price_history, NOW, 100).unwrap();
+        assert_eq!(twap_data.price, 150);
+        assert_eq!(twap_data.confidence, 2);
+        assert_eq!(twap_data.exponent, -6);
+        assert_eq!(twap_data.window_secs, 100);
+        assert_eq!(twap_data.status, PriceStatus::Trading as u8);
+    }
+
+    #[test]
+    fn test_twap_stale() {
+        let price_history = price_history(&[(NOW - 100, 100)]);
+
+        assert_eq!(
+            twap(&trading_price_account(-8), &price_history, NOW + 31, 100),
+            Err(ProgramError::Custom(PriceError::StalePrice as u32))
+        );
+    }
+
+    #[test]
+    fn test_twap_not_trading() {
+        let price_history = price_history(&[(NOW - 100, 100)]);
+
+        for status in [PriceStatus::Halted, PriceStatus::Unknown] {
+            let price_account = PriceAccount {
+                status: status as u8,
+                ..trading_price_account(-8)
+            };
+
+            assert_eq!(
+                twap(&price_account, &price_history, NOW, 100),
+                Err(ProgramError::Custom(PriceError::PriceNotTrading as u32))
+            );
+        }
+    }
+}
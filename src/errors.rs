@@ -0,0 +1,107 @@
+use pinocchio::program_error::ProgramError;
+use thiserror::Error;
+
+/// Program errors, returned as `ProgramError::Custom(code)`. Codes are stable: new variants
+/// are only ever appended.
+#[repr(u32)]
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum PriceError {
+    #[error("Signer is not the price account authority")]
+    InvalidAuthority = 0,
+    #[error("No authority transfer is pending")]
+    NoPendingAuthority = 1,
+    #[error("Signer is not the pending authority")]
+    InvalidPendingAuthority = 2,
+    #[error("Exponent is outside the supported range")]
+    InvalidExponent = 3,
+    #[error("Confidence interval exceeds the feed's maximum ratio")]
+    ConfidenceTooWide = 4,
+    #[error("Account discriminator does not match a price account")]
+    InvalidDiscriminator = 5,
+    #[error("Price account layout version is not supported")]
+    UnsupportedVersion = 6,
+    #[error("Price is older than the allowed staleness")]
+    StalePrice = 7,
+    #[error("Publish slot is older than the last accepted update")]
+    StaleUpdate = 8,
+    #[error("A required signature is missing")]
+    MissingRequiredSignature = 9,
+    #[error("Incorrect number of accounts")]
+    IncorrectNumberOfAccounts = 10,
+    #[error("Price account address does not match the expected PDA")]
+    InvalidPriceAccountAddress = 11,
+    #[error("Account is not owned by the price program")]
+    InvalidAccountOwner = 12,
+    #[error("Account data has the wrong size")]
+    InvalidAccountSize = 13,
+    #[error("Account data is not aligned for zero-copy access")]
+    MisalignedAccountData = 14,
+    #[error("Invalid price status")]
+    InvalidStatus = 15,
+    #[error("Price or confidence cannot be represented at the feed exponent")]
+    InvalidPrice = 16,
+    #[error("Return data is too short")]
+    InvalidReturnData = 17,
+    #[error("Signer is not an authorized publisher of this feed")]
+    UnknownPublisher = 18,
+    #[error("Minimum publisher quorum is outside the supported range")]
+    InvalidQuorum = 19,
+    #[error("Publish slot is ahead of the current slot")]
+    InvalidPublishSlot = 20,
+    #[error("Publisher is already authorized for this feed")]
+    DuplicatePublisher = 21,
+    #[error("All publisher slots are in use")]
+    PublisherSetFull = 22,
+    #[error("Price history address does not match the expected PDA")]
+    InvalidPriceHistoryAddress = 23,
+    #[error("TWAP window reaches further back than the retained history")]
+    InsufficientHistory = 24,
+    #[error("Price moved further from the previous price than the feed allows")]
+    DeviationTooLarge = 25,
+    #[error("Feed status is not Trading")]
+    PriceNotTrading = 26,
+    #[error("Feed does not accept negative prices")]
+    NegativePrice = 27,
+    #[error("Feed does not accept a zero price")]
+    ZeroPrice = 28,
+    #[error("Price is below the feed's minimum")]
+    PriceBelowMinimum = 29,
+    #[error("Price is above the feed's maximum")]
+    PriceAboveMaximum = 30,
+    #[error("Minimum price is greater than the maximum price")]
+    InvalidPriceBounds = 31,
+    #[error("Feed has no price to adjust")]
+    NoBasePrice = 32,
+    #[error("Unknown price adjustment kind")]
+    InvalidAdjustment = 33,
+    #[error("Symbol or asset identifier is empty or not zero-padded ASCII")]
+    InvalidMetadata = 34,
+    #[error("Unknown price account seed kind")]
+    InvalidSeedKind = 35,
+    #[error("Symbol of a symbol-derived feed cannot change")]
+    SymbolImmutable = 36,
+    #[error("Config address does not match the expected PDA")]
+    InvalidConfigAddress = 37,
+    #[error("Signer is not the program config admin")]
+    InvalidConfigAdmin = 38,
+    #[error("Feed status can only move between Trading and Halted")]
+    InvalidStatusTransition = 39,
+    #[error("Feed has not published a price yet")]
+    PriceNotPublished = 40,
+    #[error("Program data account is not this program's upgradeable loader state")]
+    InvalidProgramData = 41,
+    #[error("Signer is not the program upgrade authority")]
+    InvalidUpgradeAuthority = 42,
+    #[error("TWAP window must be at least one second")]
+    InvalidTwapWindow = 43,
+    #[error("Config flag must be 0 or 1")]
+    InvalidConfigFlag = 44,
+    #[error("Close destination cannot be the price account or its history")]
+    InvalidCloseDestination = 45,
+}
+
+impl From<PriceError> for ProgramError {
+    fn from(error: PriceError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
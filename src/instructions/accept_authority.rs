@@ -0,0 +1,193 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+
+use super::price_update::load_signed;
+use crate::errors::PriceError;
+
+/// Completes a transfer started by `ProposeAuthority`. Must be signed by the pending authority.
+pub fn process_accept_authority(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 8 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [pending_authority, price_account] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let mut price_account_mut = load_signed(pending_authority, price_account, price_account_id)?;
+
+    if price_account_mut.pending_authority == Pubkey::default() {
+        return Err(PriceError::NoPendingAuthority.into());
+    };
+
+    if price_account_mut.pending_authority != *pending_authority.key() {
+        return Err(PriceError::InvalidPendingAuthority.into());
+    };
+
+    price_account_mut.authority = price_account_mut.pending_authority;
+    price_account_mut.pending_authority = Pubkey::default();
+
+    Ok(())
+}
+
+// =================== TESTING process_accept_authority ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::result::Check;
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, ZeroCopy},
+        test_utils::{mock_price_account, mollusk, price_account_address, price_account_data, program_account, signer_account, AUTHORITY, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const NEW_AUTHORITY: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const WRONG_ACCEPTOR: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn mock_price_account_data(authority: &Pubkey, pending_authority: &Pubkey, bump: u8) -> Vec<u8> {
+        price_account_data(&PriceAccount {
+            authority: authority.to_bytes(),
+            pending_authority: pending_authority.to_bytes(),
+            ..mock_price_account(bump)
+        })
+    }
+
+    fn read_price_account(data: &[u8]) -> PriceAccount {
+        PriceAccount::load(data).unwrap()
+    }
+
+    fn propose_authority_instruction(signer: Pubkey, price_account_pda: Pubkey, price_account_id: u64, new_authority: &Pubkey) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(4u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+        instruction_data.extend_from_slice(new_authority.as_ref());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(price_account_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn accept_authority_instruction(signer: Pubkey, price_account_pda: Pubkey, price_account_id: u64) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(5u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(price_account_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    #[test]
+    fn test_process_accept_authority() {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(&AUTHORITY, &Pubkey::default(), bump));
+
+        let result = mollusk.process_and_validate_instruction_chain(
+            &[
+                (&propose_authority_instruction(AUTHORITY, price_account_pda, price_account_id, &NEW_AUTHORITY), &[Check::success()]),
+                (&accept_authority_instruction(NEW_AUTHORITY, price_account_pda, price_account_id), &[Check::success()]),
+            ],
+            &[
+                (AUTHORITY, signer_account()),
+                (NEW_AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+        );
+
+        let updated = read_price_account(&result.get_account(&price_account_pda).unwrap().data);
+        assert_eq!(updated.authority, NEW_AUTHORITY.to_bytes());
+        assert_eq!(updated.pending_authority, [0u8; 32]);
+    }
+
+    #[test]
+    fn test_process_accept_authority_after_cancel() {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(&AUTHORITY, &Pubkey::default(), bump));
+
+        mollusk.process_and_validate_instruction_chain(
+            &[
+                (&propose_authority_instruction(AUTHORITY, price_account_pda, price_account_id, &NEW_AUTHORITY), &[Check::success()]),
+                (&propose_authority_instruction(AUTHORITY, price_account_pda, price_account_id, &Pubkey::default()), &[Check::success()]),
+                (&accept_authority_instruction(NEW_AUTHORITY, price_account_pda, price_account_id), &[Check::err(ProgramError::Custom(PriceError::NoPendingAuthority as u32))]),
+            ],
+            &[
+                (AUTHORITY, signer_account()),
+                (NEW_AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_process_accept_authority_wrong_acceptor() {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(&AUTHORITY, &NEW_AUTHORITY, bump));
+
+        mollusk.process_and_validate_instruction(
+            &accept_authority_instruction(WRONG_ACCEPTOR, price_account_pda, price_account_id),
+            &[
+                (WRONG_ACCEPTOR, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPendingAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_accept_authority_compute_units() {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(&AUTHORITY, &NEW_AUTHORITY, bump));
+
+        let result = mollusk.process_and_validate_instruction(
+            &accept_authority_instruction(NEW_AUTHORITY, price_account_pda, price_account_id),
+            &[
+                (NEW_AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,208 @@
+use pinocchio::{account_info::AccountInfo, pubkey::Pubkey, ProgramResult};
+
+use crate::{errors::PriceError, states::{Config, ZeroCopy}};
+
+/// Completes a transfer started by `ProposeConfigAdmin`. Must be signed by the pending admin.
+pub fn process_accept_config_admin(accounts: &[AccountInfo], _instruction_data: &[u8]) -> ProgramResult {
+
+    let [pending_admin, config] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    if !pending_admin.is_signer() {
+        return Err(PriceError::MissingRequiredSignature.into());
+    };
+
+    let mut config_mut = Config::from_account_info_mut(config)?;
+    config_mut.verify_address(config.key())?;
+
+    if config_mut.pending_admin == Pubkey::default() {
+        return Err(PriceError::NoPendingAuthority.into());
+    };
+
+    if config_mut.pending_admin != *pending_admin.key() {
+        return Err(PriceError::InvalidPendingAuthority.into());
+    };
+
+    config_mut.admin = config_mut.pending_admin;
+    config_mut.pending_admin = Pubkey::default();
+
+    Ok(())
+}
+
+// =================== TESTING process_accept_config_admin ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::result::Check;
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{Config, ZeroCopy},
+        test_utils::{mock_config, mollusk, program_account, signer_account, PROGRAM_ID},
+    };
+
+    const ADMIN: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const NEW_ADMIN: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const WRONG_ACCEPTOR: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn pending_config(pending_admin: &Pubkey) -> (Pubkey, Account) {
+        let (config_pda, config_account) = mock_config(ADMIN, 0);
+
+        let config = Config {
+            pending_admin: pending_admin.to_bytes(),
+            ..Config::load(&config_account.data).unwrap()
+        };
+
+        let mut data = vec![0u8; Config::SIZE];
+        config.store(&mut data).unwrap();
+
+        (config_pda, program_account(data))
+    }
+
+    fn propose_config_admin_instruction(signer: Pubkey, config_pda: Pubkey, new_admin: &Pubkey) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(17u8);
+        instruction_data.extend_from_slice(new_admin.as_ref());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(config_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn accept_config_admin_instruction(signer: Pubkey, config_pda: Pubkey) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(config_pda, false),
+            ],
+            data: vec![18u8],
+        }
+    }
+
+    fn update_config_instruction(signer: Pubkey, config_pda: Pubkey) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(16u8);
+        instruction_data.push(0u8);
+        instruction_data.extend_from_slice(&120u64.to_le_bytes());
+        instruction_data.extend_from_slice(&500u16.to_le_bytes());
+        instruction_data.extend_from_slice(&10u64.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(config_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    #[test]
+    fn test_process_accept_config_admin() {
+        let mollusk = mollusk();
+
+        let (config_pda, config_account) = mock_config(ADMIN, 0);
+
+        let result = mollusk.process_and_validate_instruction_chain(
+            &[
+                (&propose_config_admin_instruction(ADMIN, config_pda, &NEW_ADMIN), &[Check::success()]),
+                (&accept_config_admin_instruction(NEW_ADMIN, config_pda), &[Check::success()]),
+            ],
+            &[
+                (ADMIN, signer_account()),
+                (NEW_ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+        );
+
+        let updated = Config::load(&result.get_account(&config_pda).unwrap().data).unwrap();
+        assert_eq!(updated.admin, NEW_ADMIN.to_bytes());
+        assert_eq!(updated.pending_admin, [0u8; 32]);
+    }
+
+    #[test]
+    fn test_process_accept_config_admin_previous_admin() {
+        let mollusk = mollusk();
+
+        let (config_pda, config_account) = mock_config(ADMIN, 0);
+
+        mollusk.process_and_validate_instruction_chain(
+            &[
+                (&propose_config_admin_instruction(ADMIN, config_pda, &NEW_ADMIN), &[Check::success()]),
+                (&update_config_instruction(ADMIN, config_pda), &[Check::success()]),
+                (&accept_config_admin_instruction(NEW_ADMIN, config_pda), &[Check::success()]),
+                (&update_config_instruction(ADMIN, config_pda), &[Check::err(ProgramError::Custom(PriceError::InvalidConfigAdmin as u32))]),
+                (&update_config_instruction(NEW_ADMIN, config_pda), &[Check::success()]),
+            ],
+            &[
+                (ADMIN, signer_account()),
+                (NEW_ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_process_accept_config_admin_nothing_pending() {
+        let mollusk = mollusk();
+
+        let (config_pda, config_account) = mock_config(ADMIN, 0);
+
+        mollusk.process_and_validate_instruction(
+            &accept_config_admin_instruction(NEW_ADMIN, config_pda),
+            &[
+                (NEW_ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::NoPendingAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_accept_config_admin_wrong_acceptor() {
+        let mollusk = mollusk();
+
+        let (config_pda, config_account) = pending_config(&NEW_ADMIN);
+
+        mollusk.process_and_validate_instruction(
+            &accept_config_admin_instruction(WRONG_ACCEPTOR, config_pda),
+            &[
+                (WRONG_ACCEPTOR, signer_account()),
+                (config_pda, config_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPendingAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_accept_config_admin_compute_units() {
+        let mollusk = mollusk();
+
+        let (config_pda, config_account) = pending_config(&NEW_ADMIN);
+
+        let result = mollusk.process_and_validate_instruction(
+            &accept_config_admin_instruction(NEW_ADMIN, config_pda),
+            &[
+                (NEW_ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
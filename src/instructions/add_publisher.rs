@@ -0,0 +1,194 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::{self, Pubkey}, ProgramResult};
+use pinocchio_log::log;
+
+use super::price_update::load_for_authority;
+use crate::{errors::PriceError, states::{PriceAccount, PublisherSlot}};
+
+/// Authorizes `publisher` to submit prices to the feed by giving it the next free publisher slot.
+pub fn process_add_publisher(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 40 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [authority, price_account] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let publisher: Pubkey = instruction_data[8..40].try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let mut price_account_mut = load_for_authority(authority, price_account, price_account_id)?;
+
+    if price_account_mut.publisher_index(&publisher).is_some() {
+        return Err(PriceError::DuplicatePublisher.into());
+    };
+
+    let index = price_account_mut.num_publishers as usize;
+
+    if index >= PriceAccount::MAX_PUBLISHERS {
+        return Err(PriceError::PublisherSetFull.into());
+    };
+
+    price_account_mut.publishers[index] = PublisherSlot {
+        publisher,
+        ..Default::default()
+    };
+    price_account_mut.num_publishers += 1;
+
+    log!("Publisher added at slot {} ({} in use)", index, price_account_mut.num_publishers);
+    pubkey::log(&publisher);
+
+    Ok(())
+}
+
+// =================== TESTING process_add_publisher ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PublisherSlot, ZeroCopy},
+        test_utils::{mock_price_account, mollusk, price_account_address, price_account_data, program_account, signer_account, AUTHORITY, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const NOT_AUTHORITY: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const PUBLISHER: Pubkey = Pubkey::new_from_array([11u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn mock_price_account_data(publishers: &[Pubkey], bump: u8) -> Vec<u8> {
+        let mut price_account = PriceAccount {
+            num_publishers: publishers.len() as u8,
+            min_publishers: 1,
+            ..mock_price_account(bump)
+        };
+
+        for (slot, publisher) in price_account.publishers.iter_mut().zip(publishers.iter()) {
+            *slot = PublisherSlot {
+                publisher: publisher.to_bytes(),
+                ..Default::default()
+            };
+        }
+
+        price_account_data(&price_account)
+    }
+
+    fn add_publisher_instruction(signer: Pubkey, price_account_pda: Pubkey, price_account_id: u64, publisher: &Pubkey) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(7u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+        instruction_data.extend_from_slice(publisher.as_ref());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(price_account_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn setup(publishers: &[Pubkey]) -> (Mollusk, u64, Pubkey, Account) {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(publishers, bump));
+
+        (mollusk, price_account_id, price_account_pda, price_account)
+    }
+
+    #[test]
+    fn test_process_add_publisher() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(&[Pubkey::new_from_array([10u8; 32])]);
+
+        let result = mollusk.process_and_validate_instruction(
+            &add_publisher_instruction(AUTHORITY, price_account_pda, price_account_id, &PUBLISHER),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.num_publishers, 2);
+        assert_eq!(updated.publishers[1].publisher, PUBLISHER.to_bytes());
+        assert_eq!(updated.publishers[1].publish_slot, 0);
+    }
+
+    #[test]
+    fn test_process_add_publisher_duplicate() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(&[PUBLISHER]);
+
+        mollusk.process_and_validate_instruction(
+            &add_publisher_instruction(AUTHORITY, price_account_pda, price_account_id, &PUBLISHER),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::DuplicatePublisher as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_add_publisher_full() {
+        let publishers: Vec<Pubkey> = (0..PriceAccount::MAX_PUBLISHERS as u8)
+            .map(|seed| Pubkey::new_from_array([100 + seed; 32]))
+            .collect();
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(&publishers);
+
+        mollusk.process_and_validate_instruction(
+            &add_publisher_instruction(AUTHORITY, price_account_pda, price_account_id, &PUBLISHER),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::PublisherSetFull as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_add_publisher_not_authority() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(&[]);
+
+        mollusk.process_and_validate_instruction(
+            &add_publisher_instruction(NOT_AUTHORITY, price_account_pda, price_account_id, &PUBLISHER),
+            &[
+                (NOT_AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_add_publisher_compute_units() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(&[]);
+
+        let result = mollusk.process_and_validate_instruction(
+            &add_publisher_instruction(AUTHORITY, price_account_pda, price_account_id, &PUBLISHER),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
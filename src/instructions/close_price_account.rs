@@ -0,0 +1,310 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{rent::Rent, Sysvar}, ProgramResult};
+use pinocchio_log::log;
+
+use super::price_update::load_for_authority;
+use crate::{errors::PriceError, states::{PriceAccount, PriceHistory, ZeroCopy}};
+
+/// Decommissions a feed: both the price account and its history are zeroed, stamped with a
+/// closed discriminator and their lamports are moved to `destination`. The price account is
+/// left behind as a rent-exempt tombstone holding only its closed discriminator, so the feed
+/// address can never be created again, in this or any later transaction. The history is
+/// closed outright; it can only be recreated together with its feed.
+pub fn process_close_price_account(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 8 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [authority, price_account, destination, price_history] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    if destination.key() == price_account.key() || destination.key() == price_history.key() {
+        return Err(PriceError::InvalidCloseDestination.into());
+    };
+
+    {
+        let price_account_data = load_for_authority(authority, price_account, price_account_id)?;
+
+        let price_history_data = PriceHistory::from_account_info(price_history)?;
+        price_history_data.verify_feed(price_account.key(), price_account_data.price_history_bump)?;
+    }
+
+    close_account(price_history, destination, PriceHistory::CLOSED_DISCRIMINATOR, false)?;
+    close_account(price_account, destination, PriceAccount::CLOSED_DISCRIMINATOR, true)?;
+
+    log!("Price account {} closed", price_account_id);
+
+    Ok(())
+}
+
+/// Zeroes `account`, stamps it with `closed_discriminator` and moves its lamports to
+/// `destination`. With `tombstone`, the account is shrunk to the discriminator and keeps the
+/// rent-exempt minimum for it.
+fn close_account(account: &AccountInfo, destination: &AccountInfo, closed_discriminator: [u8; 8], tombstone: bool) -> ProgramResult {
+    {
+        let mut data = account.try_borrow_mut_data()?;
+        data.fill(0);
+        data[..8].copy_from_slice(&closed_discriminator);
+    }
+
+    let remaining_lamports = if tombstone {
+        account.resize(closed_discriminator.len())?;
+        Rent::get()?.minimum_balance(closed_discriminator.len())
+    } else {
+        0
+    };
+
+    let lamports = account
+        .lamports()
+        .checked_sub(remaining_lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    *destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    *account.try_borrow_mut_lamports()? = remaining_lamports;
+
+    Ok(())
+}
+
+// =================== TESTING process_close_price_account ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{program, Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceHistory, PriceStatus},
+        test_utils::{mock_config, mock_price_account, mock_price_history, mollusk, price_account_address, price_account_data, system_account, CreatePriceAccountArgs, AUTHORITY, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const NOT_AUTHORITY: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const DESTINATION: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const PRICE_ACCOUNT_LAMPORTS: u64 = 5_000_000;
+    const PRICE_HISTORY_LAMPORTS: u64 = 15_000_000;
+    const MAX_COMPUTE_UNITS: u64 = 5_000;
+
+    fn setup() -> (Mollusk, u64, Pubkey, Pubkey, Vec<(Pubkey, Account)>) {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let (price_history_pda, price_history) = mock_price_history(&price_account_pda);
+
+        let price_account = PriceAccount {
+            price: 10_000_000_000,
+            status: PriceStatus::Trading as u8,
+            ..mock_price_account(bump)
+        };
+
+        let (system_program_id, system_program_account) = program::keyed_account_for_system_program();
+
+        let accounts = vec![
+            (AUTHORITY, system_account(100_000_000)),
+            (NOT_AUTHORITY, system_account(100_000_000)),
+            (price_account_pda, Account {
+                lamports: PRICE_ACCOUNT_LAMPORTS,
+                data: price_account_data(&price_account),
+                owner: PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            }),
+            (DESTINATION, system_account(0)),
+            (price_history_pda, Account {
+                lamports: PRICE_HISTORY_LAMPORTS,
+                ..price_history
+            }),
+            (system_program_id, system_program_account),
+        ];
+
+        (mollusk, price_account_id, price_account_pda, price_history_pda, accounts)
+    }
+
+    fn close_price_account_instruction(authority: Pubkey, price_account_pda: Pubkey, price_history_pda: Pubkey, price_account_id: u64) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(12u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(authority, true),
+                AccountMeta::new(price_account_pda, false),
+                AccountMeta::new(DESTINATION, false),
+                AccountMeta::new(price_history_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    #[test]
+    fn test_process_close_price_account() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, accounts) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &close_price_account_instruction(AUTHORITY, price_account_pda, price_history_pda, price_account_id),
+            &accounts,
+            &[Check::success()],
+        );
+
+        let tombstone_lamports = mollusk.sysvars.rent.minimum_balance(8);
+
+        let destination = result.get_account(&DESTINATION).unwrap();
+        assert_eq!(destination.lamports, PRICE_ACCOUNT_LAMPORTS - tombstone_lamports + PRICE_HISTORY_LAMPORTS);
+
+        let price_account = result.get_account(&price_account_pda).unwrap();
+        assert_eq!(price_account.lamports, tombstone_lamports);
+        assert_eq!(price_account.owner, PROGRAM_ID);
+        assert_eq!(price_account.data, PriceAccount::CLOSED_DISCRIMINATOR);
+
+        let price_history = result.get_account(&price_history_pda).unwrap();
+        assert_eq!(price_history.lamports, 0);
+        assert_eq!(price_history.data[..8], PriceHistory::CLOSED_DISCRIMINATOR);
+        assert!(price_history.data[8..].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn test_process_close_price_account_not_authority() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, accounts) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &close_price_account_instruction(NOT_AUTHORITY, price_account_pda, price_history_pda, price_account_id),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_close_price_account_destination_is_history() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, accounts) = setup();
+
+        let mut instruction = close_price_account_instruction(AUTHORITY, price_account_pda, price_history_pda, price_account_id);
+        instruction.accounts[2] = AccountMeta::new(price_history_pda, false);
+
+        mollusk.process_and_validate_instruction(
+            &instruction,
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidCloseDestination as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_close_price_account_then_set_price() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, accounts) = setup();
+
+        let mut instruction_data = vec![];
+        instruction_data.push(1u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+        instruction_data.extend_from_slice(&10_000_000_000i64.to_le_bytes());
+        instruction_data.extend_from_slice(&0u64.to_le_bytes());
+        instruction_data.extend_from_slice(&(-8i32).to_le_bytes());
+        instruction_data.extend_from_slice(&0u64.to_le_bytes());
+
+        let set_price_instruction = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(AUTHORITY, true),
+                AccountMeta::new(price_account_pda, false),
+                AccountMeta::new(solana_sdk::system_program::id(), false),
+                AccountMeta::new(price_history_pda, false),
+            ],
+            data: instruction_data,
+        };
+
+        mollusk.process_and_validate_instruction_chain(
+            &[
+                (&close_price_account_instruction(AUTHORITY, price_account_pda, price_history_pda, price_account_id), &[Check::success()]),
+                (&set_price_instruction, &[Check::err(ProgramError::Custom(PriceError::InvalidAccountSize as u32))]),
+            ],
+            &accounts,
+        );
+    }
+
+    #[test]
+    fn test_process_close_price_account_then_reinitialize() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, mut accounts) = setup();
+
+        let (config_pda, config_account) = mock_config(AUTHORITY, 0);
+        accounts.push((config_pda, config_account));
+
+        mollusk.process_and_validate_instruction_chain(
+            &[
+                (&close_price_account_instruction(AUTHORITY, price_account_pda, price_history_pda, price_account_id), &[Check::success()]),
+                (&CreatePriceAccountArgs::default().instruction(AUTHORITY, config_pda), &[Check::err(ProgramError::AccountAlreadyInitialized)]),
+            ],
+            &accounts,
+        );
+    }
+
+    #[test]
+    fn test_process_close_price_account_then_reinitialize_next_transaction() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, mut accounts) = setup();
+
+        let (config_pda, config_account) = mock_config(AUTHORITY, 0);
+        accounts.push((config_pda, config_account));
+
+        let result = mollusk.process_and_validate_instruction_chain(
+            &[
+                (&close_price_account_instruction(AUTHORITY, price_account_pda, price_history_pda, price_account_id), &[Check::success()]),
+            ],
+            &accounts,
+        );
+
+        // Between transactions the runtime deletes every account left without lamports.
+        let accounts: Vec<(Pubkey, Account)> = result
+            .resulting_accounts
+            .into_iter()
+            .map(|(key, account)| if account.lamports == 0 { (key, Account::default()) } else { (key, account) })
+            .collect();
+
+        assert_eq!(accounts.iter().find(|(key, _)| *key == price_history_pda).unwrap().1, Account::default());
+
+        mollusk.process_and_validate_instruction(
+            &CreatePriceAccountArgs::default().instruction(AUTHORITY, config_pda),
+            &accounts,
+            &[Check::err(ProgramError::AccountAlreadyInitialized)],
+        );
+    }
+
+    #[test]
+    fn test_process_close_price_account_twice() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, accounts) = setup();
+
+        let instruction = close_price_account_instruction(AUTHORITY, price_account_pda, price_history_pda, price_account_id);
+
+        mollusk.process_and_validate_instruction_chain(
+            &[
+                (&instruction, &[Check::success()]),
+                (&instruction, &[Check::err(ProgramError::Custom(PriceError::InvalidAccountSize as u32))]),
+            ],
+            &accounts,
+        );
+    }
+
+    #[test]
+    fn test_process_close_price_account_compute_units() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, accounts) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &close_price_account_instruction(AUTHORITY, price_account_pda, price_history_pda, price_account_id),
+            &accounts,
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
@@ -1,24 +1,34 @@
-use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::log, sysvars::{clock::Clock, rent::Rent, Sysvar}, *};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, sysvars::{clock::Clock, rent::Rent, Sysvar}, *};
 use pinocchio_system::instructions::CreateAccount;
-use pinocchio_log::log;
-
-use crate::states::{PriceAccount};
 
+use crate::{
+    errors::PriceError,
+    seeds::{price_account_seeds, price_account_symbol_seeds, price_history_seeds},
+    states::{Config, PriceAccount, PriceAccountSeeds, PriceHistory, ZeroCopy},
+};
+
+/// The feed address is derived from `price_account_id` or, when `seed_kind` is `Symbol`, from
+/// the symbol, in which case `price_account_id` is only used in logs and clients pass 0.
+///
+/// Unless the config allows permissionless creation, `payer` must be the config admin. A
+/// `max_staleness_secs`, `max_deviation_bps` or `deviation_window_slots` set to its type's
+/// maximum inherits the config default; 0 is stored as given. Pass `i64::MIN` / `i64::MAX` as
+/// `min_price` / `max_price` for an unbounded feed.
 pub fn process_create_price_account(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
-    if accounts.len() < 3 || instruction_data.len() < 8 {
-        return Err(ProgramError::InvalidAccountData);
+    if instruction_data.len() < 92 {
+        return Err(ProgramError::InvalidInstructionData);
     };
 
-    let [signer, price_account, _system_program] = accounts else {
-        return Err(ProgramError::InvalidAccountData);
+    let [signer, price_account, _system_program, price_history, config] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
     };
 
     if !signer.is_signer() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(PriceError::MissingRequiredSignature.into());
     };
 
-    if price_account.data_len() != 0 {
+    if price_account.data_len() != 0 || price_history.data_len() != 0 {
         return Err(ProgramError::AccountAlreadyInitialized);
     };
 
@@ -26,21 +36,85 @@ pub fn process_create_price_account(accounts: &[AccountInfo], instruction_data:
         instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    let (price_account_pda, bump) = pubkey::find_program_address(
-        &[b"price_feed_account", price_account_id.to_le_bytes().as_ref()],
-        &crate::ID
+    let exponent = i32::from_le_bytes(
+        instruction_data[8..12].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    if *price_account.key() != price_account_pda {
-        return Err(ProgramError::InvalidAccountData);
+    let max_confidence_ratio_bps = u16::from_le_bytes(
+        instruction_data[12..14].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let max_staleness_secs = u64::from_le_bytes(
+        instruction_data[14..22].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let min_publishers = instruction_data[22];
+
+    let ema_half_life_slots = u64::from_le_bytes(
+        instruction_data[23..31].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let max_deviation_bps = u16::from_le_bytes(
+        instruction_data[31..33].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let deviation_window_slots = u64::from_le_bytes(
+        instruction_data[33..41].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let allow_negative = instruction_data[41];
+
+    let min_price = i64::from_le_bytes(
+        instruction_data[42..50].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let max_price = i64::from_le_bytes(
+        instruction_data[50..58].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let symbol: [u8; 16] = instruction_data[58..74].try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+    let base_asset: [u8; 8] = instruction_data[74..82].try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+    let quote_asset: [u8; 8] = instruction_data[82..90].try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+    let decimals = instruction_data[90];
+    let seed_kind = PriceAccountSeeds::try_from(instruction_data[91])?;
+
+    if !(PriceAccount::MIN_EXPONENT..=PriceAccount::MAX_EXPONENT).contains(&exponent) {
+        return Err(PriceError::InvalidExponent.into());
+    };
+
+    if !(1..=PriceAccount::MAX_PUBLISHERS).contains(&(min_publishers as usize)) {
+        return Err(PriceError::InvalidQuorum.into());
+    };
+
+    if min_price > max_price || (allow_negative == 0 && max_price <= 0) {
+        return Err(PriceError::InvalidPriceBounds.into());
+    };
+
+    PriceAccount::check_metadata(&symbol, &base_asset, &quote_asset)?;
+
+    let (max_staleness_secs, max_deviation_bps, deviation_window_slots) = {
+        let config_data = Config::from_account_info(config)?;
+        config_data.verify_address(config.key())?;
+        config_data.check_creator(signer.key())?;
+        config_data.feed_defaults(max_staleness_secs, max_deviation_bps, deviation_window_slots)
+    };
+
+    let price_account_id_bytes = price_account_id.to_le_bytes();
+    let [prefix, seed] = match seed_kind {
+        PriceAccountSeeds::Id => price_account_seeds(&price_account_id_bytes),
+        PriceAccountSeeds::Symbol => price_account_symbol_seeds(&symbol),
     };
 
-    let price_account_id_clone = price_account_id.to_le_bytes();
+    let (price_account_pda, bump) = pubkey::find_program_address(&[prefix, seed], &crate::ID);
+
+    if *price_account.key() != price_account_pda {
+        return Err(PriceError::InvalidPriceAccountAddress.into());
+    };
 
     let bump_arr = [bump];
     let seeds = seeds!(
-        b"price_feed_account",
-        price_account_id_clone.as_ref(),
+        prefix,
+        seed,
         &bump_arr
     );
 
@@ -52,193 +126,278 @@ pub fn process_create_price_account(accounts: &[AccountInfo], instruction_data:
         owner: &crate::ID
     }.invoke_signed(&[Signer::from(&seeds)])?;
 
-    let mut price_account_mut = PriceAccount::from_account_info_mut(price_account)?;
-    price_account_mut.price = 0.0;
-    price_account_mut.last_updated_timestamp = Clock::get()?.unix_timestamp;
+    let price_history_bump = create_price_history(signer, price_account, price_history)?;
+
+    let mut price_account_mut = PriceAccount::init_header(price_account)?;
+    price_account_mut.price = 0;
+    price_account_mut.confidence = 0;
+    price_account_mut.exponent = exponent;
+    price_account_mut.max_confidence_ratio_bps = max_confidence_ratio_bps;
+    let clock = Clock::get()?;
+    price_account_mut.last_updated_timestamp = clock.unix_timestamp;
+    price_account_mut.last_updated_slot = clock.slot;
+    price_account_mut.authority = *signer.key();
     price_account_mut.price_account_bump = bump;
+    price_account_mut.price_history_bump = price_history_bump;
+    price_account_mut.max_staleness_secs = max_staleness_secs;
+    price_account_mut.min_publishers = min_publishers;
+    price_account_mut.ema_half_life_slots = ema_half_life_slots;
+    price_account_mut.max_deviation_bps = max_deviation_bps;
+    price_account_mut.deviation_window_slots = deviation_window_slots;
+    price_account_mut.allow_negative = allow_negative;
+    price_account_mut.min_price = min_price;
+    price_account_mut.max_price = max_price;
+    price_account_mut.set_metadata(symbol, base_asset, quote_asset, decimals)?;
+    price_account_mut.seed_kind = seed_kind as u8;
 
     Ok(())
 }
 
+/// Creates and initializes the `["price_history", price_account]` PDA, funded by `payer`, and
+/// returns its bump.
+pub(crate) fn create_price_history(payer: &AccountInfo, price_account: &AccountInfo, price_history: &AccountInfo) -> Result<u8, ProgramError> {
+    let [prefix, seed] = price_history_seeds(price_account.key());
+    let (price_history_pda, price_history_bump) = pubkey::find_program_address(&[prefix, seed], &crate::ID);
+
+    if *price_history.key() != price_history_pda {
+        return Err(PriceError::InvalidPriceHistoryAddress.into());
+    };
+
+    let price_history_bump_arr = [price_history_bump];
+    let price_history_seeds = seeds!(
+        prefix,
+        seed,
+        &price_history_bump_arr
+    );
+
+    CreateAccount {
+        from: payer,
+        to: price_history,
+        lamports: Rent::get()?.minimum_balance(PriceHistory::SIZE),
+        space: PriceHistory::SIZE as u64,
+        owner: &crate::ID
+    }.invoke_signed(&[Signer::from(&price_history_seeds)])?;
+
+    PriceHistory::init(price_history, price_account.key(), price_history_bump)?;
+
+    Ok(price_history_bump)
+}
+
 // =================== TESTING process_create_price_account =================== 
 
 #[cfg(test)]
 mod tests {
-    use mollusk_svm::{program, Mollusk, result::Check};
+    use mollusk_svm::result::{Check, InstructionResult};
     use solana_sdk::{
         account::Account,
-        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
         pubkey::Pubkey,
     };
 
-    const PROGRAM_ID: Pubkey = solana_sdk::pubkey!("4zSrGy87rYtohmWK7PLBsojskZQa38GMwmoQkeK1nJSD");
-    const SIGNER: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceAccountSeeds, PriceHistory, ZeroCopy},
+        test_utils::{mock_config, mollusk, price_history_address, CreatePriceAccountArgs, AUTHORITY},
+    };
+
+    const NOT_ADMIN: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 20_000;
+
+    fn process_create(args: &CreatePriceAccountArgs, signer: Pubkey, config: (Pubkey, Account), check: Check) -> InstructionResult {
+        let instruction = args.instruction(signer, config.0);
+        let accounts = args.accounts(signer, config);
+
+        mollusk().process_and_validate_instruction(&instruction, &accounts, &[check])
+    }
 
     #[test]
     fn test_process_create_price_account() {
-        let mollusk = Mollusk::new(&PROGRAM_ID, "target/deploy/pinocchio_price_account");
-
-        let price_account_id = 13u64;
-        
-        let mut instruction_data = vec![];
-        instruction_data.push(0u8); 
-        instruction_data.extend_from_slice(&price_account_id.to_le_bytes()); 
-        
-        println!("Full instruction_data: {:?}", instruction_data);
-
-        let (price_account_pda, _bump) = Pubkey::find_program_address(
-            &[b"price_feed_account", price_account_id.to_le_bytes().as_ref()],
-            &PROGRAM_ID
+        let args = CreatePriceAccountArgs::default();
+        let price_account_pda = args.price_account_address();
+        let (price_history_pda, _) = price_history_address(&price_account_pda);
+
+        let result = process_create(&args, AUTHORITY, mock_config(AUTHORITY, 0), Check::success());
+
+        let price_history = PriceHistory::load(&result.get_account(&price_history_pda).unwrap().data).unwrap();
+        assert_eq!(price_history.discriminator, PriceHistory::DISCRIMINATOR);
+        assert_eq!(price_history.price_account, price_account_pda.to_bytes());
+        assert_eq!(price_history.len, 0);
+        let price_history_bump = price_history.bump;
+
+        let price_account = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(&price_account.symbol[..7], b"SOL/USD");
+        assert_eq!(price_account.base_asset, *b"SOL\0\0\0\0\0");
+        assert_eq!(price_account.quote_asset, *b"USD\0\0\0\0\0");
+        assert_eq!(price_account.decimals, 9);
+        assert_eq!(price_account.price_history_bump, price_history_bump);
+    }
+
+    #[test]
+    fn test_process_create_price_account_symbol_seeded() {
+        let args = CreatePriceAccountArgs { price_account_id: 0, seed_kind: PriceAccountSeeds::Symbol, ..Default::default() };
+        let price_account_pda = args.price_account_address();
+        let (price_history_pda, _) = price_history_address(&price_account_pda);
+
+        let result = process_create(&args, AUTHORITY, mock_config(AUTHORITY, 0), Check::success());
+
+        let price_history = PriceHistory::load(&result.get_account(&price_history_pda).unwrap().data).unwrap();
+        assert_eq!(price_history.price_account, price_account_pda.to_bytes());
+
+        let price_account = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(&price_account.symbol[..7], b"SOL/USD");
+        assert_eq!(price_account.seed_kind, PriceAccountSeeds::Symbol as u8);
+    }
+
+    #[test]
+    fn test_process_create_price_account_invalid_pda() {
+        let args = CreatePriceAccountArgs::default();
+        let (config_pda, config_account) = mock_config(AUTHORITY, 0);
+
+        let mut instruction = args.instruction(AUTHORITY, config_pda);
+        let mut accounts = args.accounts(AUTHORITY, (config_pda, config_account));
+
+        let wrong_pda = CreatePriceAccountArgs { price_account_id: 123, ..Default::default() }.price_account_address();
+        instruction.accounts[1].pubkey = wrong_pda;
+        accounts[1].0 = wrong_pda;
+
+        mollusk().process_and_validate_instruction(
+            &instruction,
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPriceAccountAddress as u32))],
         );
+    }
+
+    #[test]
+    fn test_process_create_price_account_invalid_signer() {
+        let args = CreatePriceAccountArgs::default();
+        let (config_pda, config_account) = mock_config(AUTHORITY, 0);
 
-        let (system_program_id, system_account) = program::keyed_account_for_system_program();
-
-        let instruction = Instruction {
-            program_id: PROGRAM_ID,
-            accounts: vec![
-                AccountMeta::new(SIGNER, true),
-                AccountMeta::new(price_account_pda, false),
-                AccountMeta::new(system_program_id, false),
-            ],
-            data: instruction_data,
-        };
-
-        let signer_account = Account {
-            lamports: 10_000_000,
-            data: vec![],
-            owner: solana_sdk::system_program::id(),
-            executable: false,
-            rent_epoch: 0,
-        };
-
-        let price_account = Account {
-            lamports: 0,
-            data: vec![],
-            owner: solana_sdk::system_program::id(),
-            executable: false,
-            rent_epoch: 0,
-        };
-
-        mollusk.process_and_validate_instruction(
+        let mut instruction = args.instruction(AUTHORITY, config_pda);
+        instruction.accounts[0].is_signer = false;
+
+        mollusk().process_and_validate_instruction(
             &instruction,
-            &vec![
-                (SIGNER, signer_account),
-                (price_account_pda, price_account),
-                (system_program_id, system_account),
-            ],
-            &[Check::success()],
+            &args.accounts(AUTHORITY, (config_pda, config_account)),
+            &[Check::err(ProgramError::Custom(PriceError::MissingRequiredSignature as u32))],
         );
     }
 
     #[test]
-    fn test_process_create_price_account_invalid_pda() {
-        let mollusk = Mollusk::new(&PROGRAM_ID, "target/deploy/price_account");
-
-        let price_account_id = 123u64;
-        
-        let mut instruction_data = vec![];
-        instruction_data.push(0u8); 
-        instruction_data.extend_from_slice(&price_account_id.to_le_bytes()); 
-        
-        println!("Full instruction_data: {:?}", instruction_data);
-
-        let (price_account_pda, _bump) = Pubkey::find_program_address(
-            &[b"price_account", price_account_id.to_le_bytes().as_ref()],
-            &PROGRAM_ID
+    fn test_process_create_price_account_invalid_exponent() {
+        process_create(
+            &CreatePriceAccountArgs { exponent: -19, ..Default::default() },
+            AUTHORITY,
+            mock_config(AUTHORITY, 0),
+            Check::err(ProgramError::Custom(PriceError::InvalidExponent as u32)),
         );
+    }
 
-        let (system_program_id, system_account) = program::keyed_account_for_system_program();
-
-        let instruction = Instruction {
-            program_id: PROGRAM_ID,
-            accounts: vec![
-                AccountMeta::new(SIGNER, true),
-                AccountMeta::new(price_account_pda, false),
-                AccountMeta::new(system_program_id, false),
-            ],
-            data: instruction_data,
-        };
-
-        let signer_account = Account {
-            lamports: 10_000_000,
-            data: vec![],
-            owner: solana_sdk::system_program::id(),
-            executable: false,
-            rent_epoch: 0,
-        };
-
-        let price_account = Account {
-            lamports: 0,
-            data: vec![],
-            owner: solana_sdk::system_program::id(),
-            executable: false,
-            rent_epoch: 0,
-        };
-
-        mollusk.process_and_validate_instruction(
-            &instruction,
-            &vec![
-                (SIGNER, signer_account),
-                (price_account_pda, price_account),
-                (system_program_id, system_account),
-            ],
-            &[Check::success()],
+    #[test]
+    fn test_process_create_price_account_invalid_quorum() {
+        process_create(
+            &CreatePriceAccountArgs { min_publishers: 0, ..Default::default() },
+            AUTHORITY,
+            mock_config(AUTHORITY, 0),
+            Check::err(ProgramError::Custom(PriceError::InvalidQuorum as u32)),
         );
     }
 
-     #[test]
-    fn test_process_create_price_account_invalid_signer() {
-        let mollusk = Mollusk::new(&PROGRAM_ID, "target/deploy/price_account");
-
-        let price_account_id = 123u64;
-        
-        let mut instruction_data = vec![];
-        instruction_data.push(0u8); 
-        instruction_data.extend_from_slice(&price_account_id.to_le_bytes()); 
-        
-        println!("Full instruction_data: {:?}", instruction_data);
-
-        let (price_account_pda, _bump) = Pubkey::find_program_address(
-            &[b"price_feed_account", price_account_id.to_le_bytes().as_ref()],
-            &PROGRAM_ID
+    #[test]
+    fn test_process_create_price_account_invalid_metadata() {
+        process_create(
+            &CreatePriceAccountArgs { symbol: [0u8; 16], ..Default::default() },
+            AUTHORITY,
+            mock_config(AUTHORITY, 0),
+            Check::err(ProgramError::Custom(PriceError::InvalidMetadata as u32)),
         );
+    }
 
-        let (system_program_id, system_account) = program::keyed_account_for_system_program();
-
-        let instruction = Instruction {
-            program_id: PROGRAM_ID,
-            accounts: vec![
-                AccountMeta::new(SIGNER, false),
-                AccountMeta::new(price_account_pda, false),
-                AccountMeta::new(system_program_id, false),
-            ],
-            data: instruction_data,
-        };
-
-        let signer_account = Account {
-            lamports: 10_000_000,
-            data: vec![],
-            owner: solana_sdk::system_program::id(),
-            executable: false,
-            rent_epoch: 0,
-        };
-
-        let price_account = Account {
-            lamports: 0,
-            data: vec![],
-            owner: solana_sdk::system_program::id(),
-            executable: false,
-            rent_epoch: 0,
-        };
-
-        mollusk.process_and_validate_instruction(
-            &instruction,
-            &vec![
-                (SIGNER, signer_account),
-                (price_account_pda, price_account),
-                (system_program_id, system_account),
-            ],
+    #[test]
+    fn test_process_create_price_account_invalid_price_bounds() {
+        process_create(
+            &CreatePriceAccountArgs { min_price: 20_000_000_000, max_price: 10_000_000_000, ..Default::default() },
+            AUTHORITY,
+            mock_config(AUTHORITY, 0),
+            Check::err(ProgramError::Custom(PriceError::InvalidPriceBounds as u32)),
+        );
+    }
+
+    #[test]
+    fn test_process_create_price_account_no_positive_price() {
+        process_create(
+            &CreatePriceAccountArgs { max_price: 0, ..Default::default() },
+            AUTHORITY,
+            mock_config(AUTHORITY, 0),
+            Check::err(ProgramError::Custom(PriceError::InvalidPriceBounds as u32)),
+        );
+    }
+
+    #[test]
+    fn test_process_create_price_account_compute_units() {
+        let args = CreatePriceAccountArgs::default();
+        let (config_pda, config_account) = mock_config(AUTHORITY, 0);
+
+        let result = mollusk().process_and_validate_instruction(
+            &args.instruction(AUTHORITY, config_pda),
+            &args.accounts(AUTHORITY, (config_pda, config_account)),
             &[Check::success()],
         );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_process_create_price_account_admin_only() {
+        process_create(
+            &CreatePriceAccountArgs::default(),
+            NOT_ADMIN,
+            mock_config(AUTHORITY, 0),
+            Check::err(ProgramError::Custom(PriceError::InvalidConfigAdmin as u32)),
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_process_create_price_account_permissionless() {
+        let args = CreatePriceAccountArgs::default();
+
+        let result = process_create(&args, NOT_ADMIN, mock_config(AUTHORITY, 1), Check::success());
+
+        let price_account = PriceAccount::load(&result.get_account(&args.price_account_address()).unwrap().data).unwrap();
+        assert_eq!(price_account.authority, NOT_ADMIN.to_bytes());
+    }
+
+    #[test]
+    fn test_process_create_price_account_inherits_defaults() {
+        let args = CreatePriceAccountArgs { max_staleness_secs: u64::MAX, max_deviation_bps: u16::MAX, deviation_window_slots: u64::MAX, ..Default::default() };
+
+        let result = process_create(&args, AUTHORITY, mock_config(AUTHORITY, 0), Check::success());
+
+        let price_account = PriceAccount::load(&result.get_account(&args.price_account_address()).unwrap().data).unwrap();
+        assert_eq!(price_account.max_staleness_secs, 120);
+        assert_eq!(price_account.max_deviation_bps, 500);
+        assert_eq!(price_account.deviation_window_slots, 10);
+    }
+
+    #[test]
+    fn test_process_create_price_account_circuit_breaker_disabled() {
+        let args = CreatePriceAccountArgs { max_deviation_bps: 0, deviation_window_slots: 0, ..Default::default() };
+
+        let result = process_create(&args, AUTHORITY, mock_config(AUTHORITY, 0), Check::success());
+
+        let price_account = PriceAccount::load(&result.get_account(&args.price_account_address()).unwrap().data).unwrap();
+        assert_eq!(price_account.max_deviation_bps, 0);
+        assert_eq!(price_account.deviation_window_slots, 0);
+    }
+
+    #[test]
+    fn test_process_create_price_account_wrong_config() {
+        let (_config_pda, config_account) = mock_config(AUTHORITY, 0);
+
+        process_create(
+            &CreatePriceAccountArgs::default(),
+            AUTHORITY,
+            (Pubkey::new_unique(), config_account),
+            Check::err(ProgramError::Custom(PriceError::InvalidConfigAddress as u32)),
+        );
+    }
+}
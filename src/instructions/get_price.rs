@@ -1,16 +1,453 @@
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError,*};
+use pinocchio::{account_info::AccountInfo, cpi::set_return_data, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, ProgramResult};
 
-pub fn process_get_price(accounts: &[AccountInfo]) -> ProgramResult {
+use crate::{errors::PriceError, states::{PriceAccount, ZeroCopy}};
 
-    let [signer, price_account, system_program] = accounts else {
-        return Err(ProgramError::InvalidAccountData);
+/// Return data emitted by `GetPrice`. All fields are little-endian:
+///
+/// | offset | size | field                          |
+/// |--------|------|--------------------------------|
+/// | 0      | 8    | `price` (i64)                  |
+/// | 8      | 8    | `confidence` (u64)             |
+/// | 16     | 4    | `exponent` (i32)               |
+/// | 20     | 8    | `last_updated_timestamp` (i64) |
+/// | 28     | 1    | `status` (`PriceStatus`)       |
+/// | 29     | 8    | `last_updated_slot` (u64)      |
+/// | 37     | 8    | `ema_price` (i64)              |
+/// | 45     | 8    | `ema_confidence` (u64)         |
+/// | 53     | 16   | `symbol`                       |
+/// | 69     | 8    | `base_asset`                   |
+/// | 77     | 8    | `quote_asset`                  |
+/// | 85     | 1    | `decimals` (u8)                |
+///
+/// Programs reading a feed via CPI can decode the result of `get_return_data` with
+/// [`PriceData::from_bytes`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceData {
+    pub price: i64,
+    pub confidence: u64,
+    pub exponent: i32,
+    pub last_updated_timestamp: i64,
+    pub status: u8,
+    pub last_updated_slot: u64,
+    pub ema_price: i64,
+    pub ema_confidence: u64,
+    pub symbol: [u8; 16],
+    pub base_asset: [u8; 8],
+    pub quote_asset: [u8; 8],
+    pub decimals: u8,
+}
+
+impl PriceData {
+    pub const LEN: usize = 86;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+        data[0..8].copy_from_slice(&self.price.to_le_bytes());
+        data[8..16].copy_from_slice(&self.confidence.to_le_bytes());
+        data[16..20].copy_from_slice(&self.exponent.to_le_bytes());
+        data[20..28].copy_from_slice(&self.last_updated_timestamp.to_le_bytes());
+        data[28] = self.status;
+        data[29..37].copy_from_slice(&self.last_updated_slot.to_le_bytes());
+        data[37..45].copy_from_slice(&self.ema_price.to_le_bytes());
+        data[45..53].copy_from_slice(&self.ema_confidence.to_le_bytes());
+        data[53..69].copy_from_slice(&self.symbol);
+        data[69..77].copy_from_slice(&self.base_asset);
+        data[77..85].copy_from_slice(&self.quote_asset);
+        data[85] = self.decimals;
+        data
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < Self::LEN {
+            return Err(PriceError::InvalidReturnData.into());
+        };
+
+        Ok(PriceData {
+            price: i64::from_le_bytes(data[0..8].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            confidence: u64::from_le_bytes(data[8..16].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            exponent: i32::from_le_bytes(data[16..20].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            last_updated_timestamp: i64::from_le_bytes(data[20..28].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            status: data[28],
+            last_updated_slot: u64::from_le_bytes(data[29..37].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            ema_price: i64::from_le_bytes(data[37..45].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            ema_confidence: u64::from_le_bytes(data[45..53].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            symbol: data[53..69].try_into().map_err(|_| PriceError::InvalidReturnData)?,
+            base_asset: data[69..77].try_into().map_err(|_| PriceError::InvalidReturnData)?,
+            quote_asset: data[77..85].try_into().map_err(|_| PriceError::InvalidReturnData)?,
+            decimals: data[85],
+        })
+    }
+}
+
+impl From<&PriceAccount> for PriceData {
+    fn from(price_account: &PriceAccount) -> Self {
+        PriceData {
+            price: price_account.price,
+            confidence: price_account.confidence,
+            exponent: price_account.exponent,
+            last_updated_timestamp: price_account.last_updated_timestamp,
+            status: price_account.status,
+            last_updated_slot: price_account.last_updated_slot,
+            ema_price: price_account.ema_price,
+            ema_confidence: price_account.ema_confidence,
+            symbol: price_account.symbol,
+            base_asset: price_account.base_asset,
+            quote_asset: price_account.quote_asset,
+            decimals: price_account.decimals,
+        }
+    }
+}
+
+/// Instruction data is `price_account_id: u64`, `max_staleness_secs: u64` and
+/// `max_staleness_slots: u64`. `max_staleness_secs` optionally tightens the feed's own time
+/// bound and `max_staleness_slots` adds a bound on slots since the last update; `0` disables
+/// either. Fails with `StalePrice` when the price is older than either bound. A `Halted` or
+/// `Unknown` feed is still returned, with its status, so callers must check `status`.
+pub fn process_get_price(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 24 {
+        return Err(ProgramError::InvalidInstructionData);
     };
 
-    if !signer.is_signer() {
-        return Err(ProgramError::InvalidAccountData);
+    let [price_account] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
     };
 
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let max_staleness_override = u64::from_le_bytes(
+        instruction_data[8..16].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
 
+    let max_staleness_slots = u64::from_le_bytes(
+        instruction_data[16..24].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    if !price_account.is_owned_by(&crate::ID) {
+        return Err(PriceError::InvalidAccountOwner.into());
+    };
+
+    if price_account.data_len() != PriceAccount::SIZE {
+        return Err(PriceError::InvalidAccountSize.into());
+    };
+
+    let price_account_data = PriceAccount::from_account_info(price_account)?;
+    price_account_data.verify_address(price_account.key(), price_account_id)?;
+
+    let clock = Clock::get()?;
+
+    price_account_data.check_staleness(
+        clock.unix_timestamp,
+        (max_staleness_override != 0).then_some(max_staleness_override)
+    )?;
+
+    if max_staleness_slots != 0 {
+        price_account_data.check_slot_staleness(clock.slot, max_staleness_slots)?;
+    };
+
+    let price_data = PriceData::from(&*price_account_data);
+
+    set_return_data(&price_data.to_bytes());
 
     Ok(())
+}
+
+// =================== TESTING process_get_price ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::result::Check;
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use super::PriceData;
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceStatus, ZeroCopy},
+        test_utils::{mock_price_account, mollusk, price_account_address, price_account_data, program_account, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn trading_price_account(price: i64, last_updated_timestamp: i64, bump: u8) -> PriceAccount {
+        PriceAccount {
+            price,
+            confidence: 25,
+            exponent: -2,
+            last_updated_timestamp,
+            status: PriceStatus::Trading as u8,
+            max_staleness_secs: 60,
+            last_updated_slot: 1_000,
+            ema_price: 14_300,
+            ema_confidence: 30,
+            symbol: *b"SOL/USD\0\0\0\0\0\0\0\0\0",
+            base_asset: *b"SOL\0\0\0\0\0",
+            quote_asset: *b"USD\0\0\0\0\0",
+            decimals: 9,
+            ..mock_price_account(bump)
+        }
+    }
+
+    fn mock_price_account_data(price: i64, last_updated_timestamp: i64, bump: u8) -> Vec<u8> {
+        price_account_data(&trading_price_account(price, last_updated_timestamp, bump))
+    }
+
+    fn get_price_instruction(price_account_pda: Pubkey, price_account_id: u64) -> Instruction {
+        get_price_instruction_with_max_staleness(price_account_pda, price_account_id, 0, 0)
+    }
+
+    fn get_price_instruction_with_max_staleness(price_account_pda: Pubkey, price_account_id: u64, max_staleness_secs: u64, max_staleness_slots: u64) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(3u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+        instruction_data.extend_from_slice(&max_staleness_secs.to_le_bytes());
+        instruction_data.extend_from_slice(&max_staleness_slots.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(price_account_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    #[test]
+    fn test_price_data_round_trip() {
+        let price_data = PriceData {
+            price: 14_250,
+            confidence: 25,
+            exponent: -2,
+            last_updated_timestamp: 1_700_000_000,
+            status: PriceStatus::Trading as u8,
+            last_updated_slot: 1_000,
+            ema_price: 14_300,
+            ema_confidence: 30,
+            symbol: *b"SOL/USD\0\0\0\0\0\0\0\0\0",
+            base_asset: *b"SOL\0\0\0\0\0",
+            quote_asset: *b"USD\0\0\0\0\0",
+            decimals: 9,
+        };
+
+        assert_eq!(PriceData::from_bytes(&price_data.to_bytes()).unwrap(), price_data);
+        assert!(PriceData::from_bytes(&price_data.to_bytes()[..PriceData::LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn test_process_get_price() {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(14_250, 1_700_000_000, bump));
+
+        let expected = PriceData {
+            price: 14_250,
+            confidence: 25,
+            exponent: -2,
+            last_updated_timestamp: 1_700_000_000,
+            status: PriceStatus::Trading as u8,
+            last_updated_slot: 1_000,
+            ema_price: 14_300,
+            ema_confidence: 30,
+            symbol: *b"SOL/USD\0\0\0\0\0\0\0\0\0",
+            base_asset: *b"SOL\0\0\0\0\0",
+            quote_asset: *b"USD\0\0\0\0\0",
+            decimals: 9,
+        }.to_bytes();
+
+        mollusk.process_and_validate_instruction(
+            &get_price_instruction(price_account_pda, price_account_id),
+            &[(price_account_pda, price_account)],
+            &[Check::success(), Check::return_data(&expected)],
+        );
+    }
+
+    #[test]
+    fn test_process_get_price_wrong_owner() {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = Account {
+            lamports: 1_000_000,
+            data: mock_price_account_data(14_250, 1_700_000_000, bump),
+            owner: solana_sdk::system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        mollusk.process_and_validate_instruction(
+            &get_price_instruction(price_account_pda, price_account_id),
+            &[(price_account_pda, price_account)],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAccountOwner as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_get_price_wrong_discriminator() {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(price_account_data(&PriceAccount {
+            discriminator: *b"NOTPRICE",
+            ..trading_price_account(14_250, 1_700_000_000, bump)
+        }));
+
+        mollusk.process_and_validate_instruction(
+            &get_price_instruction(price_account_pda, price_account_id),
+            &[(price_account_pda, price_account)],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidDiscriminator as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_get_price_wrong_version() {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(price_account_data(&PriceAccount {
+            version: PriceAccount::VERSION + 1,
+            ..trading_price_account(14_250, 1_700_000_000, bump)
+        }));
+
+        mollusk.process_and_validate_instruction(
+            &get_price_instruction(price_account_pda, price_account_id),
+            &[(price_account_pda, price_account)],
+            &[Check::err(ProgramError::Custom(PriceError::UnsupportedVersion as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_get_price_wrong_pda() {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(14_250, 1_700_000_000, bump));
+
+        mollusk.process_and_validate_instruction(
+            &get_price_instruction(price_account_pda, price_account_id + 1),
+            &[(price_account_pda, price_account)],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPriceAccountAddress as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_get_price_compute_units() {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(14_250, 1_700_000_000, bump));
+
+        let expected = PriceData {
+            price: 14_250,
+            confidence: 25,
+            exponent: -2,
+            last_updated_timestamp: 1_700_000_000,
+            status: PriceStatus::Trading as u8,
+            last_updated_slot: 1_000,
+            ema_price: 14_300,
+            ema_confidence: 30,
+            symbol: *b"SOL/USD\0\0\0\0\0\0\0\0\0",
+            base_asset: *b"SOL\0\0\0\0\0",
+            quote_asset: *b"USD\0\0\0\0\0",
+            decimals: 9,
+        }.to_bytes();
+
+        let result = mollusk.process_and_validate_instruction(
+            &get_price_instruction(price_account_pda, price_account_id),
+            &[(price_account_pda, price_account)],
+            &[Check::success(), Check::return_data(&expected)],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_process_get_price_stale() {
+        let mut mollusk = mollusk();
+        mollusk.sysvars.clock.unix_timestamp = 1_700_000_061;
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(14_250, 1_700_000_000, bump));
+
+        mollusk.process_and_validate_instruction(
+            &get_price_instruction(price_account_pda, price_account_id),
+            &[(price_account_pda, price_account)],
+            &[Check::err(ProgramError::Custom(PriceError::StalePrice as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_get_price_max_staleness_override() {
+        let mut mollusk = mollusk();
+        mollusk.sysvars.clock.unix_timestamp = 1_700_000_030;
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(14_250, 1_700_000_000, bump));
+
+        mollusk.process_and_validate_instruction(
+            &get_price_instruction_with_max_staleness(price_account_pda, price_account_id, 30, 0),
+            &[(price_account_pda, price_account.clone())],
+            &[Check::success()],
+        );
+
+        mollusk.process_and_validate_instruction(
+            &get_price_instruction_with_max_staleness(price_account_pda, price_account_id, 29, 0),
+            &[(price_account_pda, price_account)],
+            &[Check::err(ProgramError::Custom(PriceError::StalePrice as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_get_price_stale_slots() {
+        let mut mollusk = mollusk();
+        mollusk.warp_to_slot(1_025);
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(14_250, 1_700_000_000, bump));
+
+        mollusk.process_and_validate_instruction(
+            &get_price_instruction_with_max_staleness(price_account_pda, price_account_id, 0, 25),
+            &[(price_account_pda, price_account.clone())],
+            &[Check::success()],
+        );
+
+        mollusk.process_and_validate_instruction(
+            &get_price_instruction_with_max_staleness(price_account_pda, price_account_id, 0, 24),
+            &[(price_account_pda, price_account)],
+            &[Check::err(ProgramError::Custom(PriceError::StalePrice as u32))],
+        );
+    }
 }
\ No newline at end of file
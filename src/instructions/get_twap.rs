@@ -0,0 +1,283 @@
+use pinocchio::{account_info::AccountInfo, cpi::set_return_data, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, ProgramResult};
+
+use crate::{errors::PriceError, states::{PriceAccount, PriceHistory, ZeroCopy}};
+
+/// Return data emitted by `GetTwap`. All fields are little-endian:
+///
+/// | offset | size | field                    |
+/// |--------|------|--------------------------|
+/// | 0      | 8    | `price` (i64)            |
+/// | 8      | 8    | `confidence` (u64)       |
+/// | 16     | 4    | `exponent` (i32)         |
+/// | 20     | 8    | `window_secs` (u64)      |
+/// | 28     | 1    | `status` (`PriceStatus`) |
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TwapData {
+    pub price: i64,
+    pub confidence: u64,
+    pub exponent: i32,
+    pub window_secs: u64,
+    pub status: u8,
+}
+
+impl TwapData {
+    pub const LEN: usize = 29;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+        data[0..8].copy_from_slice(&self.price.to_le_bytes());
+        data[8..16].copy_from_slice(&self.confidence.to_le_bytes());
+        data[16..20].copy_from_slice(&self.exponent.to_le_bytes());
+        data[20..28].copy_from_slice(&self.window_secs.to_le_bytes());
+        data[28] = self.status;
+        data
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < Self::LEN {
+            return Err(PriceError::InvalidReturnData.into());
+        };
+
+        Ok(TwapData {
+            price: i64::from_le_bytes(data[0..8].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            confidence: u64::from_le_bytes(data[8..16].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            exponent: i32::from_le_bytes(data[16..20].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            window_secs: u64::from_le_bytes(data[20..28].try_into().map_err(|_| PriceError::InvalidReturnData)?),
+            status: data[28],
+        })
+    }
+}
+
+/// Instruction data is `price_account_id: u64` and `window_secs: u64`. Returns the
+/// time-weighted average over the last `window_secs` seconds, failing with `InvalidTwapWindow`
+/// for a window of 0, with `InsufficientHistory` if the history does not cover the whole
+/// window and with `StalePrice` if the feed itself is stale. As with `GetPrice`, a `Halted`
+/// or `Unknown` feed is still averaged and returned with its status, so callers must check
+/// `status`.
+pub fn process_get_twap(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 16 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [price_account, price_history] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let window_secs = u64::from_le_bytes(
+        instruction_data[8..16].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    if window_secs == 0 {
+        return Err(PriceError::InvalidTwapWindow.into());
+    };
+
+    let price_account_data = PriceAccount::from_account_info(price_account)?;
+    price_account_data.verify_address(price_account.key(), price_account_id)?;
+
+    let price_history_data = PriceHistory::from_account_info(price_history)?;
+    price_history_data.verify_feed(price_account.key(), price_account_data.price_history_bump)?;
+
+    let clock = Clock::get()?;
+    price_account_data.check_staleness(clock.unix_timestamp, None)?;
+
+    let (price, confidence) = price_history_data.twap(clock.unix_timestamp, window_secs)?;
+
+    let twap_data = TwapData {
+        price,
+        confidence,
+        exponent: price_account_data.exponent,
+        window_secs,
+        status: price_account_data.status,
+    };
+
+    set_return_data(&twap_data.to_bytes());
+
+    Ok(())
+}
+
+// =================== TESTING process_get_twap ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use super::TwapData;
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceHistory, PriceHistoryEntry, PriceStatus, ZeroCopy},
+        test_utils::{mock_price_account, mock_price_history, mollusk, price_account_address, price_account_data, program_account, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const NOW: i64 = 1_700_000_060;
+    const MAX_COMPUTE_UNITS: u64 = 6_000;
+
+    fn setup() -> (Mollusk, u64, Pubkey, Pubkey, Vec<(Pubkey, Account)>) {
+        let mut mollusk = mollusk();
+        mollusk.sysvars.clock.unix_timestamp = NOW;
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let (price_history_pda, price_history_account) = mock_price_history(&price_account_pda);
+
+        let price_account = PriceAccount {
+            price: 400,
+            confidence: 40,
+            exponent: -2,
+            last_updated_timestamp: NOW - 10,
+            status: PriceStatus::Trading as u8,
+            max_staleness_secs: 60,
+            ..mock_price_account(bump)
+        };
+
+        let mut price_history = PriceHistory::load(&price_history_account.data).unwrap();
+        price_history.append(PriceHistoryEntry { price: 100, confidence: 10, timestamp: NOW - 60, slot: 1 });
+        price_history.append(PriceHistoryEntry { price: 200, confidence: 20, timestamp: NOW - 30, slot: 2 });
+        price_history.append(PriceHistoryEntry { price: 400, confidence: 40, timestamp: NOW - 10, slot: 3 });
+
+        let mut price_history_data = vec![0u8; PriceHistory::SIZE];
+        price_history.store(&mut price_history_data).unwrap();
+
+        let accounts = vec![
+            (price_account_pda, program_account(price_account_data(&price_account))),
+            (price_history_pda, program_account(price_history_data)),
+        ];
+
+        (mollusk, price_account_id, price_account_pda, price_history_pda, accounts)
+    }
+
+    fn get_twap_instruction(price_account_pda: Pubkey, price_history_pda: Pubkey, price_account_id: u64, window_secs: u64) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(9u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+        instruction_data.extend_from_slice(&window_secs.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(price_account_pda, false),
+                AccountMeta::new_readonly(price_history_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    #[test]
+    fn test_twap_data_round_trip() {
+        let twap_data = TwapData {
+            price: 183,
+            confidence: 18,
+            exponent: -2,
+            window_secs: 60,
+            status: PriceStatus::Trading as u8,
+        };
+
+        assert_eq!(TwapData::from_bytes(&twap_data.to_bytes()).unwrap(), twap_data);
+        assert!(TwapData::from_bytes(&twap_data.to_bytes()[..TwapData::LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn test_process_get_twap() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, accounts) = setup();
+
+        let expected = TwapData {
+            price: 183,
+            confidence: 18,
+            exponent: -2,
+            window_secs: 60,
+            status: PriceStatus::Trading as u8,
+        }.to_bytes();
+
+        mollusk.process_and_validate_instruction(
+            &get_twap_instruction(price_account_pda, price_history_pda, price_account_id, 60),
+            &accounts,
+            &[Check::success(), Check::return_data(&expected)],
+        );
+    }
+
+    #[test]
+    fn test_process_get_twap_halted() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, mut accounts) = setup();
+
+        let price_account = PriceAccount {
+            status: PriceStatus::Halted as u8,
+            ..PriceAccount::load(&accounts[0].1.data).unwrap()
+        };
+        accounts[0].1.data = price_account_data(&price_account);
+
+        let expected = TwapData {
+            price: 183,
+            confidence: 18,
+            exponent: -2,
+            window_secs: 60,
+            status: PriceStatus::Halted as u8,
+        }.to_bytes();
+
+        mollusk.process_and_validate_instruction(
+            &get_twap_instruction(price_account_pda, price_history_pda, price_account_id, 60),
+            &accounts,
+            &[Check::success(), Check::return_data(&expected)],
+        );
+    }
+
+    #[test]
+    fn test_process_get_twap_window_exceeds_history() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, accounts) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &get_twap_instruction(price_account_pda, price_history_pda, price_account_id, 61),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InsufficientHistory as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_get_twap_zero_window() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, accounts) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &get_twap_instruction(price_account_pda, price_history_pda, price_account_id, 0),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidTwapWindow as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_get_twap_wrong_history() {
+        let (mollusk, price_account_id, price_account_pda, _price_history_pda, mut accounts) = setup();
+
+        let (wrong_history, wrong_history_account) = mock_price_history(&Pubkey::new_unique());
+        accounts[1] = (wrong_history, wrong_history_account);
+
+        mollusk.process_and_validate_instruction(
+            &get_twap_instruction(price_account_pda, wrong_history, price_account_id, 60),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPriceHistoryAddress as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_get_twap_compute_units() {
+        let (mollusk, price_account_id, price_account_pda, price_history_pda, accounts) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &get_twap_instruction(price_account_pda, price_history_pda, price_account_id, 60),
+            &accounts,
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
@@ -0,0 +1,302 @@
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, sysvars::{rent::Rent, Sysvar}, *};
+use pinocchio_log::log;
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{errors::PriceError, seeds::{config_seeds, program_data_seeds, BPF_LOADER_UPGRADEABLE_ID}, states::{Config, ZeroCopy}};
+
+/// Creates the program-wide `Config` singleton with `payer` as its admin. `payer` must be the
+/// program's upgrade authority, read from its `ProgramData` account, so that nobody can claim
+/// the config between deployment and initialization.
+///
+/// Instruction data is `permissionless_creation: u8`, `default_max_staleness_secs: u64`,
+/// `default_max_deviation_bps: u16` and `default_deviation_window_slots: u64`. The config can
+/// only be initialized once; afterwards it is changed with `UpdateConfig`.
+pub fn process_initialize_config(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 19 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [payer, config, _system_program, program_data] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let permissionless_creation = instruction_data[0];
+
+    let default_max_staleness_secs = u64::from_le_bytes(
+        instruction_data[1..9].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let default_max_deviation_bps = u16::from_le_bytes(
+        instruction_data[9..11].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let default_deviation_window_slots = u64::from_le_bytes(
+        instruction_data[11..19].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    if permissionless_creation > 1 {
+        return Err(PriceError::InvalidConfigFlag.into());
+    };
+
+    if !payer.is_signer() {
+        return Err(PriceError::MissingRequiredSignature.into());
+    };
+
+    if !program_data.is_owned_by(&BPF_LOADER_UPGRADEABLE_ID) || *program_data.key() != pubkey::find_program_address(&program_data_seeds(), &BPF_LOADER_UPGRADEABLE_ID).0 {
+        return Err(PriceError::InvalidProgramData.into());
+    };
+
+    {
+        // `UpgradeableLoaderState::ProgramData`: a u32 tag of 3 and the u64 deployment slot,
+        // then the upgrade authority as an `Option<Pubkey>`.
+        let program_data_bytes = program_data.try_borrow_data()?;
+
+        if program_data_bytes.len() < 45 || program_data_bytes[0..4] != 3u32.to_le_bytes() {
+            return Err(PriceError::InvalidProgramData.into());
+        };
+
+        if program_data_bytes[12] != 1 || program_data_bytes[13..45] != *payer.key() {
+            return Err(PriceError::InvalidUpgradeAuthority.into());
+        };
+    }
+
+    if config.data_len() != 0 {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    };
+
+    let [prefix] = config_seeds();
+    let (config_pda, bump) = pubkey::find_program_address(&[prefix], &crate::ID);
+
+    if *config.key() != config_pda {
+        return Err(PriceError::InvalidConfigAddress.into());
+    };
+
+    let bump_arr = [bump];
+    let seeds = seeds!(
+        prefix,
+        &bump_arr
+    );
+
+    CreateAccount {
+        from: payer,
+        to: config,
+        lamports: Rent::get()?.minimum_balance(Config::SIZE),
+        space: Config::SIZE as u64,
+        owner: &crate::ID
+    }.invoke_signed(&[Signer::from(&seeds)])?;
+
+    let mut config_mut = Config::init(config, bump)?;
+    config_mut.admin = *payer.key();
+    config_mut.permissionless_creation = permissionless_creation;
+    config_mut.default_max_staleness_secs = default_max_staleness_secs;
+    config_mut.default_max_deviation_bps = default_max_deviation_bps;
+    config_mut.default_deviation_window_slots = default_deviation_window_slots;
+
+    log!("Config initialized, permissionless creation: {}", permissionless_creation);
+
+    Ok(())
+}
+
+// =================== TESTING process_initialize_config ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{program, Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        seeds::BPF_LOADER_UPGRADEABLE_ID,
+        states::{Config, ZeroCopy},
+        test_utils::{config_address, mollusk, program_data_address, system_account, PROGRAM_ID},
+    };
+
+    const PAYER: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const NOT_UPGRADE_AUTHORITY: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 10_000;
+    const LOADER_ID: Pubkey = Pubkey::new_from_array(BPF_LOADER_UPGRADEABLE_ID);
+
+    /// The program's `ProgramData` header: tag 3, deployment slot, optional upgrade authority.
+    fn program_data_account(upgrade_authority: Option<Pubkey>) -> Account {
+        let mut data = vec![];
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        match upgrade_authority {
+            Some(upgrade_authority) => {
+                data.push(1);
+                data.extend_from_slice(upgrade_authority.as_ref());
+            }
+            None => data.extend_from_slice(&[0u8; 33]),
+        };
+
+        Account {
+            lamports: 1_000_000,
+            data,
+            owner: LOADER_ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn initialize_config_instruction(config_pda: Pubkey, permissionless_creation: u8) -> Instruction {
+        let (system_program_id, _system_account) = program::keyed_account_for_system_program();
+
+        let mut instruction_data = vec![];
+        instruction_data.push(15u8);
+        instruction_data.push(permissionless_creation);
+        instruction_data.extend_from_slice(&60u64.to_le_bytes());
+        instruction_data.extend_from_slice(&500u16.to_le_bytes());
+        instruction_data.extend_from_slice(&10u64.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(PAYER, true),
+                AccountMeta::new(config_pda, false),
+                AccountMeta::new_readonly(system_program_id, false),
+                AccountMeta::new_readonly(program_data_address(), false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn setup(config_pda: Pubkey) -> (Mollusk, Vec<(Pubkey, Account)>) {
+        let mollusk = mollusk();
+
+        let accounts = vec![
+            (PAYER, system_account(100_000_000)),
+            (config_pda, system_account(0)),
+            program::keyed_account_for_system_program(),
+            (program_data_address(), program_data_account(Some(PAYER))),
+        ];
+
+        (mollusk, accounts)
+    }
+
+    #[test]
+    fn test_process_initialize_config() {
+        let (config_pda, bump) = config_address();
+        let (mollusk, accounts) = setup(config_pda);
+
+        let result = mollusk.process_and_validate_instruction(
+            &initialize_config_instruction(config_pda, 0),
+            &accounts,
+            &[Check::success()],
+        );
+
+        assert_eq!(result.get_account(&config_pda).unwrap().owner, PROGRAM_ID);
+
+        let config = Config::load(&result.get_account(&config_pda).unwrap().data).unwrap();
+        assert_eq!(config.discriminator, Config::DISCRIMINATOR);
+        assert_eq!(config.bump, bump);
+        assert_eq!(config.admin, PAYER.to_bytes());
+        assert_eq!(config.permissionless_creation, 0);
+        assert_eq!(config.default_max_staleness_secs, 60);
+        assert_eq!(config.default_max_deviation_bps, 500);
+        assert_eq!(config.default_deviation_window_slots, 10);
+    }
+
+    #[test]
+    fn test_process_initialize_config_twice() {
+        let (config_pda, _bump) = config_address();
+        let (mollusk, accounts) = setup(config_pda);
+
+        mollusk.process_and_validate_instruction_chain(
+            &[
+                (&initialize_config_instruction(config_pda, 0), &[Check::success()]),
+                (&initialize_config_instruction(config_pda, 1), &[Check::err(ProgramError::AccountAlreadyInitialized)]),
+            ],
+            &accounts,
+        );
+    }
+
+    #[test]
+    fn test_process_initialize_config_invalid_pda() {
+        let wrong_pda = Pubkey::new_unique();
+        let (mollusk, accounts) = setup(wrong_pda);
+
+        mollusk.process_and_validate_instruction(
+            &initialize_config_instruction(wrong_pda, 0),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidConfigAddress as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_initialize_config_invalid_flag() {
+        let (config_pda, _bump) = config_address();
+        let (mollusk, accounts) = setup(config_pda);
+
+        mollusk.process_and_validate_instruction(
+            &initialize_config_instruction(config_pda, 2),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidConfigFlag as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_initialize_config_not_upgrade_authority() {
+        let (config_pda, _bump) = config_address();
+        let (mollusk, mut accounts) = setup(config_pda);
+
+        accounts[3] = (program_data_address(), program_data_account(Some(NOT_UPGRADE_AUTHORITY)));
+
+        mollusk.process_and_validate_instruction(
+            &initialize_config_instruction(config_pda, 0),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidUpgradeAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_initialize_config_immutable_program() {
+        let (config_pda, _bump) = config_address();
+        let (mollusk, mut accounts) = setup(config_pda);
+
+        accounts[3] = (program_data_address(), program_data_account(None));
+
+        mollusk.process_and_validate_instruction(
+            &initialize_config_instruction(config_pda, 0),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidUpgradeAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_initialize_config_wrong_program_data() {
+        let (config_pda, _bump) = config_address();
+        let (mollusk, mut accounts) = setup(config_pda);
+
+        let wrong_program_data = Pubkey::new_unique();
+        accounts[3] = (wrong_program_data, program_data_account(Some(PAYER)));
+
+        let mut instruction = initialize_config_instruction(config_pda, 0);
+        instruction.accounts[3] = AccountMeta::new_readonly(wrong_program_data, false);
+
+        mollusk.process_and_validate_instruction(
+            &instruction,
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidProgramData as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_initialize_config_compute_units() {
+        let (config_pda, _bump) = config_address();
+        let (mollusk, accounts) = setup(config_pda);
+
+        let result = mollusk.process_and_validate_instruction(
+            &initialize_config_instruction(config_pda, 0),
+            &accounts,
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
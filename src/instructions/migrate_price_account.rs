@@ -0,0 +1,436 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::{self, Pubkey}, sysvars::{rent::Rent, Sysvar}, ProgramResult};
+use pinocchio_log::log;
+use pinocchio_system::instructions::Transfer;
+
+use super::create_price_account::create_price_history;
+use crate::{errors::PriceError, seeds::price_account_seeds, states::{Config, LegacyPriceAccountV0, PriceAccount, PriceStatus, ZeroCopy}};
+
+/// Upgrades a feed stored in the `LegacyPriceAccountV0` layout to the current `PriceAccount`
+/// layout in place, topping up rent from `admin` and creating the feed's price history.
+///
+/// Instruction data is `price_account_id: u64`, `exponent: i32`,
+/// `max_confidence_ratio_bps: u16`, `max_staleness_secs: u64`, `min_publishers: u8`,
+/// `authority: Pubkey`, `allow_negative: u8`, `min_price: i64` and `max_price: i64`.
+/// Legacy feeds have no authority, so migration is restricted to the config admin whatever the
+/// creation policy, and the admin names the migrated feed's authority. Legacy feeds also have
+/// no metadata, which is left empty until set with `UpdateMetadata`. The migrated feed inherits
+/// the config's circuit-breaker defaults, as well as its staleness default when
+/// `max_staleness_secs` is `u64::MAX`. A published legacy price that rounds to zero at
+/// `exponent` fails with `InvalidPrice` rather than leaving the feed `Unknown`.
+pub fn process_migrate_price_account(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 72 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [admin, price_account, _system_program, price_history, config] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let exponent = i32::from_le_bytes(
+        instruction_data[8..12].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let max_confidence_ratio_bps = u16::from_le_bytes(
+        instruction_data[12..14].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let max_staleness_secs = u64::from_le_bytes(
+        instruction_data[14..22].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let min_publishers = instruction_data[22];
+
+    let authority: Pubkey = instruction_data[23..55].try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let allow_negative = instruction_data[55];
+
+    let min_price = i64::from_le_bytes(
+        instruction_data[56..64].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let max_price = i64::from_le_bytes(
+        instruction_data[64..72].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    if !admin.is_signer() {
+        return Err(PriceError::MissingRequiredSignature.into());
+    };
+
+    if !price_account.is_owned_by(&crate::ID) {
+        return Err(PriceError::InvalidAccountOwner.into());
+    };
+
+    if price_account.data_len() >= PriceAccount::SIZE {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    };
+
+    if price_history.data_len() != 0 {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    };
+
+    let legacy = LegacyPriceAccountV0::load(&price_account.try_borrow_data()?)?;
+
+    let price_account_id_bytes = price_account_id.to_le_bytes();
+    let [prefix, seed] = price_account_seeds(&price_account_id_bytes);
+    let price_account_pda = pubkey::create_program_address(
+        &[prefix, seed, &[legacy.price_account_bump]],
+        &crate::ID
+    ).map_err(|_| PriceError::InvalidPriceAccountAddress)?;
+
+    if *price_account.key() != price_account_pda {
+        return Err(PriceError::InvalidPriceAccountAddress.into());
+    };
+
+    if !(PriceAccount::MIN_EXPONENT..=PriceAccount::MAX_EXPONENT).contains(&exponent) {
+        return Err(PriceError::InvalidExponent.into());
+    };
+
+    if !(1..=PriceAccount::MAX_PUBLISHERS).contains(&(min_publishers as usize)) {
+        return Err(PriceError::InvalidQuorum.into());
+    };
+
+    if min_price > max_price || (allow_negative == 0 && max_price <= 0) {
+        return Err(PriceError::InvalidPriceBounds.into());
+    };
+
+    let (max_staleness_secs, max_deviation_bps, deviation_window_slots) = {
+        let config_data = Config::from_account_info(config)?;
+        config_data.verify_address(config.key())?;
+
+        if config_data.admin != *admin.key() {
+            return Err(PriceError::InvalidConfigAdmin.into());
+        };
+
+        config_data.feed_defaults(max_staleness_secs, u16::MAX, u64::MAX)
+    };
+
+    let mut migrated = PriceAccount {
+        discriminator: PriceAccount::DISCRIMINATOR,
+        version: PriceAccount::VERSION,
+        price_account_bump: legacy.price_account_bump,
+        exponent,
+        max_confidence_ratio_bps,
+        authority,
+        max_staleness_secs,
+        min_publishers,
+        max_deviation_bps,
+        deviation_window_slots,
+        allow_negative,
+        min_price,
+        max_price,
+        ..Default::default()
+    };
+
+    let price = legacy.price_at_exponent(exponent)?;
+
+    if legacy.price != 0.0 {
+        if price == 0 {
+            return Err(PriceError::InvalidPrice.into());
+        };
+
+        migrated.validate_price(price)?;
+        migrated.price = price;
+        migrated.ema_price = price;
+        migrated.last_updated_timestamp = legacy.last_updated_timestamp;
+        migrated.status = PriceStatus::Trading as u8;
+    };
+
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(PriceAccount::SIZE);
+
+    if price_account.lamports() < rent_exempt_lamports {
+        Transfer {
+            from: admin,
+            to: price_account,
+            lamports: rent_exempt_lamports - price_account.lamports(),
+        }.invoke()?;
+    };
+
+    price_account.resize(PriceAccount::SIZE)?;
+
+    migrated.price_history_bump = create_price_history(admin, price_account, price_history)?;
+    migrated.store(&mut price_account.try_borrow_mut_data()?)?;
+
+    log!("Price account {} migrated to version {}", price_account_id, PriceAccount::VERSION);
+
+    Ok(())
+}
+
+// =================== TESTING process_migrate_price_account ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{program, Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{LegacyPriceAccountV0, PriceAccount, PriceHistory, PriceStatus, ZeroCopy},
+        test_utils::{config_address, mock_config, mollusk, price_account_address, price_history_address, system_account, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const ADMIN: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const NOT_ADMIN: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const FEED_AUTHORITY: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const LEGACY_LAMPORTS: u64 = 1_057_920;
+    const LEGACY_TIMESTAMP: i64 = 1_700_000_000;
+    const MAX_COMPUTE_UNITS: u64 = 25_000;
+
+    fn legacy_account_data(price: f64, bump: u8) -> Vec<u8> {
+        let mut data = vec![0u8; LegacyPriceAccountV0::SIZE];
+        data[0..8].copy_from_slice(&price.to_le_bytes());
+        data[8..16].copy_from_slice(&LEGACY_TIMESTAMP.to_le_bytes());
+        data[16] = bump;
+        data
+    }
+
+    fn setup(price_account_data: fn(u8) -> Vec<u8>) -> (Mollusk, Pubkey, Pubkey, Vec<(Pubkey, Account)>) {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let (price_history_pda, _price_history_bump) = price_history_address(&price_account_pda);
+
+        let accounts = vec![
+            (ADMIN, system_account(100_000_000)),
+            (price_account_pda, Account {
+                lamports: LEGACY_LAMPORTS,
+                data: price_account_data(bump),
+                owner: PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            }),
+            program::keyed_account_for_system_program(),
+            (price_history_pda, Account::default()),
+            mock_config(ADMIN, 0),
+        ];
+
+        (mollusk, price_account_pda, price_history_pda, accounts)
+    }
+
+    fn migrate_price_account_instruction(price_account_pda: Pubkey, price_history_pda: Pubkey) -> Instruction {
+        migrate_price_account_instruction_with(ADMIN, price_account_pda, price_history_pda, 0, i64::MIN, i64::MAX)
+    }
+
+    fn migrate_price_account_instruction_with(
+        signer: Pubkey,
+        price_account_pda: Pubkey,
+        price_history_pda: Pubkey,
+        allow_negative: u8,
+        min_price: i64,
+        max_price: i64,
+    ) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(13u8);
+        instruction_data.extend_from_slice(&13u64.to_le_bytes());
+        instruction_data.extend_from_slice(&(-8i32).to_le_bytes());
+        instruction_data.extend_from_slice(&100u16.to_le_bytes());
+        instruction_data.extend_from_slice(&60u64.to_le_bytes());
+        instruction_data.push(1u8);
+        instruction_data.extend_from_slice(FEED_AUTHORITY.as_ref());
+        instruction_data.push(allow_negative);
+        instruction_data.extend_from_slice(&min_price.to_le_bytes());
+        instruction_data.extend_from_slice(&max_price.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(signer, true),
+                AccountMeta::new(price_account_pda, false),
+                AccountMeta::new_readonly(solana_sdk::system_program::id(), false),
+                AccountMeta::new(price_history_pda, false),
+                AccountMeta::new_readonly(config_address().0, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    #[test]
+    fn test_process_migrate_price_account() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(|bump| legacy_account_data(123.45, bump));
+
+        let result = mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction(price_account_pda, price_history_pda),
+            &accounts,
+            &[Check::success()],
+        );
+
+        let price_account = result.get_account(&price_account_pda).unwrap();
+        assert_eq!(price_account.data.len(), PriceAccount::SIZE);
+        assert!(price_account.lamports >= mollusk.sysvars.rent.minimum_balance(PriceAccount::SIZE));
+
+        let migrated = PriceAccount::load(&price_account.data).unwrap();
+        assert_eq!(migrated.discriminator, PriceAccount::DISCRIMINATOR);
+        assert_eq!(migrated.version, PriceAccount::VERSION);
+        assert_eq!(migrated.price, 12_345_000_000);
+        assert_eq!(migrated.exponent, -8);
+        assert_eq!(migrated.last_updated_timestamp, LEGACY_TIMESTAMP);
+        assert_eq!(migrated.authority, FEED_AUTHORITY.to_bytes());
+        assert_eq!(migrated.status(), Ok(PriceStatus::Trading));
+        assert_eq!(migrated.max_staleness_secs, 60);
+        assert_eq!(migrated.max_deviation_bps, 500);
+        assert_eq!(migrated.deviation_window_slots, 10);
+        assert_eq!((migrated.min_price, migrated.max_price), (i64::MIN, i64::MAX));
+
+        let price_history = PriceHistory::load(&result.get_account(&price_history_pda).unwrap().data).unwrap();
+        assert_eq!(price_history.price_account, price_account_pda.to_bytes());
+        assert_eq!(price_history.bump, migrated.price_history_bump);
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_unpublished() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(|bump| legacy_account_data(0.0, bump));
+
+        let result = mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction(price_account_pda, price_history_pda),
+            &accounts,
+            &[Check::success()],
+        );
+
+        let migrated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(migrated.price, 0);
+        assert_eq!(migrated.status(), Ok(PriceStatus::Unknown));
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_nan_price() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(|bump| legacy_account_data(f64::NAN, bump));
+
+        mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction(price_account_pda, price_history_pda),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPrice as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_wrong_bump() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(|bump| legacy_account_data(123.45, bump.wrapping_sub(1)));
+
+        mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction(price_account_pda, price_history_pda),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPriceAccountAddress as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_already_current() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(|bump| {
+            let price_account = PriceAccount {
+                discriminator: PriceAccount::DISCRIMINATOR,
+                version: PriceAccount::VERSION,
+                price_account_bump: bump,
+                ..Default::default()
+            };
+
+            let mut data = vec![0u8; PriceAccount::SIZE];
+            price_account.store(&mut data).unwrap();
+            data
+        });
+
+        mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction(price_account_pda, price_history_pda),
+            &accounts,
+            &[Check::err(ProgramError::AccountAlreadyInitialized)],
+        );
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_negative_price() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(|bump| legacy_account_data(-12.5, bump));
+
+        let result = mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction_with(ADMIN, price_account_pda, price_history_pda, 1, i64::MIN, i64::MAX),
+            &accounts,
+            &[Check::success()],
+        );
+
+        let migrated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(migrated.price, -1_250_000_000);
+        assert_eq!(migrated.allow_negative, 1);
+        assert_eq!(migrated.status(), Ok(PriceStatus::Trading));
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_negative_price_not_allowed() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(|bump| legacy_account_data(-12.5, bump));
+
+        mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction(price_account_pda, price_history_pda),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::NegativePrice as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_price_rounds_to_zero() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(|bump| legacy_account_data(1e-10, bump));
+
+        mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction(price_account_pda, price_history_pda),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPrice as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_invalid_bounds() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(|bump| legacy_account_data(123.45, bump));
+
+        mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction_with(ADMIN, price_account_pda, price_history_pda, 0, 0, -1),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPriceBounds as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_not_admin() {
+        let (mollusk, price_account_pda, price_history_pda, mut accounts) = setup(|bump| legacy_account_data(123.45, bump));
+        accounts[0] = (NOT_ADMIN, system_account(100_000_000));
+
+        mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction_with(NOT_ADMIN, price_account_pda, price_history_pda, 0, i64::MIN, i64::MAX),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidConfigAdmin as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_permissionless_not_admin() {
+        let (mollusk, price_account_pda, price_history_pda, mut accounts) = setup(|bump| legacy_account_data(123.45, bump));
+        accounts[0] = (NOT_ADMIN, system_account(100_000_000));
+        accounts[4] = mock_config(ADMIN, 1);
+
+        mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction_with(NOT_ADMIN, price_account_pda, price_history_pda, 0, i64::MIN, i64::MAX),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidConfigAdmin as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_migrate_price_account_compute_units() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(|bump| legacy_account_data(123.45, bump));
+
+        let result = mollusk.process_and_validate_instruction(
+            &migrate_price_account_instruction(price_account_pda, price_history_pda),
+            &accounts,
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
@@ -5,6 +5,22 @@ pub mod create_price_account;
 pub mod modify_price;
 pub mod set_price;
 pub mod get_price;
+pub mod propose_authority;
+pub mod accept_authority;
+pub mod submit_price;
+pub mod add_publisher;
+pub mod remove_publisher;
+pub mod get_twap;
+pub mod override_circuit_breaker;
+pub mod set_status;
+pub mod close_price_account;
+pub mod migrate_price_account;
+pub mod update_metadata;
+pub mod initialize_config;
+pub mod update_config;
+pub mod propose_config_admin;
+pub mod accept_config_admin;
+pub(crate) mod price_update;
 
 #[repr(u8)]
 #[derive(ShankInstruction)]
@@ -12,20 +28,92 @@ pub enum PriceInstructions {
     #[account(0, writable, signer, name="payer", desc="Account that pays for account creation")]
     #[account(1, writable, name="price_account", desc="The price account to be created")]
     #[account(2, name="system_program", desc="System program")]
+    #[account(3, writable, name="price_history", desc="The price history account to be created")]
+    #[account(4, name="config", desc="Program config holding the creation policy and feed defaults")]
     CreatePriceAccount = 0,
 
     #[account(0, signer, name="signer", desc="Signer authority")]
     #[account(1, writable, name="price_account", desc="The price account to update")]
     #[account(2, name="system_program", desc="System program")]
+    #[account(3, writable, name="price_history", desc="The price history to append to")]
     SetPrice = 1,
 
     #[account(0, signer, name="signer", desc="Signer authority")]
     #[account(1, writable, name="price_account", desc="The price account to update")]
     #[account(2, name="system_program", desc="System program")]
+    #[account(3, writable, name="price_history", desc="The price history to append to")]
     ModifyPrice = 2,
 
     #[account(0, name="price_account", desc="The price account to read from")]
     GetPrice = 3,
+
+    #[account(0, signer, name="authority", desc="Current price account authority")]
+    #[account(1, writable, name="price_account", desc="The price account whose authority is being transferred")]
+    ProposeAuthority = 4,
+
+    #[account(0, signer, name="pending_authority", desc="Proposed authority accepting the transfer")]
+    #[account(1, writable, name="price_account", desc="The price account whose authority is being transferred")]
+    AcceptAuthority = 5,
+
+    #[account(0, signer, name="publisher", desc="Authorized publisher submitting a price")]
+    #[account(1, writable, name="price_account", desc="The price account to submit to")]
+    #[account(2, writable, name="price_history", desc="The price history to append aggregates to")]
+    SubmitPrice = 6,
+
+    #[account(0, signer, name="authority", desc="Price account authority")]
+    #[account(1, writable, name="price_account", desc="The price account to add the publisher to")]
+    AddPublisher = 7,
+
+    #[account(0, signer, name="authority", desc="Price account authority")]
+    #[account(1, writable, name="price_account", desc="The price account to remove the publisher from")]
+    RemovePublisher = 8,
+
+    #[account(0, name="price_account", desc="The price account to read from")]
+    #[account(1, name="price_history", desc="The price history of the price account")]
+    GetTwap = 9,
+
+    #[account(0, signer, name="authority", desc="Price account authority")]
+    #[account(1, writable, name="price_account", desc="The price account whose next update skips the deviation check")]
+    OverrideCircuitBreaker = 10,
+
+    #[account(0, signer, name="authority", desc="Price account authority")]
+    #[account(1, writable, name="price_account", desc="The price account whose status is set")]
+    SetStatus = 11,
+
+    #[account(0, signer, name="authority", desc="Price account authority")]
+    #[account(1, writable, name="price_account", desc="The price account to close")]
+    #[account(2, writable, name="destination", desc="Account receiving the reclaimed lamports")]
+    #[account(3, writable, name="price_history", desc="The price history of the price account, closed alongside it")]
+    ClosePriceAccount = 12,
+
+    #[account(0, writable, signer, name="admin", desc="Program admin; pays the rent top-up")]
+    #[account(1, writable, name="price_account", desc="The legacy price account to migrate")]
+    #[account(2, name="system_program", desc="System program")]
+    #[account(3, writable, name="price_history", desc="The price history account to be created")]
+    #[account(4, name="config", desc="Program config naming the admin and holding the feed defaults")]
+    MigratePriceAccount = 13,
+
+    #[account(0, signer, name="authority", desc="Price account authority")]
+    #[account(1, writable, name="price_account", desc="The price account whose metadata is replaced")]
+    UpdateMetadata = 14,
+
+    #[account(0, writable, signer, name="payer", desc="Program upgrade authority; pays for the config account and becomes the program admin")]
+    #[account(1, writable, name="config", desc="The config account to be created")]
+    #[account(2, name="system_program", desc="System program")]
+    #[account(3, name="program_data", desc="The program's ProgramData account naming its upgrade authority")]
+    InitializeConfig = 15,
+
+    #[account(0, signer, name="admin", desc="Program admin")]
+    #[account(1, writable, name="config", desc="The config account to update")]
+    UpdateConfig = 16,
+
+    #[account(0, signer, name="admin", desc="Current program admin")]
+    #[account(1, writable, name="config", desc="The config whose admin is being transferred")]
+    ProposeConfigAdmin = 17,
+
+    #[account(0, signer, name="pending_admin", desc="Proposed admin accepting the transfer")]
+    #[account(1, writable, name="config", desc="The config whose admin is being transferred")]
+    AcceptConfigAdmin = 18,
 }
 
 
@@ -38,6 +126,21 @@ impl TryFrom<&u8> for PriceInstructions {
             1 => Ok(PriceInstructions::SetPrice),
             2 => Ok(PriceInstructions::ModifyPrice),
             3 => Ok(PriceInstructions::GetPrice),
+            4 => Ok(PriceInstructions::ProposeAuthority),
+            5 => Ok(PriceInstructions::AcceptAuthority),
+            6 => Ok(PriceInstructions::SubmitPrice),
+            7 => Ok(PriceInstructions::AddPublisher),
+            8 => Ok(PriceInstructions::RemovePublisher),
+            9 => Ok(PriceInstructions::GetTwap),
+            10 => Ok(PriceInstructions::OverrideCircuitBreaker),
+            11 => Ok(PriceInstructions::SetStatus),
+            12 => Ok(PriceInstructions::ClosePriceAccount),
+            13 => Ok(PriceInstructions::MigratePriceAccount),
+            14 => Ok(PriceInstructions::UpdateMetadata),
+            15 => Ok(PriceInstructions::InitializeConfig),
+            16 => Ok(PriceInstructions::UpdateConfig),
+            17 => Ok(PriceInstructions::ProposeConfigAdmin),
+            18 => Ok(PriceInstructions::AcceptConfigAdmin),
             _ => Err(ProgramError::InvalidInstructionData)
         }
     }
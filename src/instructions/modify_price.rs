@@ -1,35 +1,67 @@
-use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, sysvars::{clock::Clock, rent::Rent, Sysvar}, *};
-use pinocchio_system::instructions::CreateAccount;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, ProgramResult};
 
-use crate::states::{price_account, PriceAccount};
+use super::price_update::{apply_update, load_for_update};
+use crate::{errors::PriceError, states::{PriceAccount, PriceAdjustment}};
 
+/// Instruction data is `price_account_id: u64`, `adjustment: u8`, `amount: i64`,
+/// `confidence: u64`, `exponent: i32` and `publish_slot: u64`. Moves the current price by
+/// `amount`, either as a delta at `exponent` or in basis points, and sets a new confidence.
+/// A nonzero delta too small to register at the feed exponent fails with `InvalidPrice`
+/// rather than being recorded as an unchanged price.
 pub fn process_modify_price(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
-    if instruction_data.len() < 16 {
+    if instruction_data.len() < 37 {
         return Err(ProgramError::InvalidInstructionData);
     };
 
-    let [signer, price_account, system_program] = accounts else {
-        return Err(ProgramError::InvalidAccountData);
+    let [signer, price_account, _system_program, price_history] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
     };
 
     let price_account_id = u64::from_le_bytes(
         instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    let modified_price = f64::from_le_bytes(
-        instruction_data[8..16].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    let adjustment = PriceAdjustment::try_from(instruction_data[8])?;
+
+    let amount = i64::from_le_bytes(
+        instruction_data[9..17].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    if !signer.is_signer() || price_account.data_len() == 0 {
-        return Err(ProgramError::InvalidAccountData);
-    };
+    let confidence = u64::from_le_bytes(
+        instruction_data[17..25].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let exponent = i32::from_le_bytes(
+        instruction_data[25..29].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let publish_slot = u64::from_le_bytes(
+        instruction_data[29..37].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
 
-    let mut price_account_mut = PriceAccount::from_account_info_mut(price_account)?;
-    price_account_mut.price = modified_price;
-    price_account_mut.last_updated_timestamp = Clock::get()?.unix_timestamp;
+    let clock = Clock::get()?;
 
-    Ok(())
+    let mut price_account_mut = load_for_update(signer, price_account, price_account_id, publish_slot, &clock)?;
+
+    let amount = match adjustment {
+        PriceAdjustment::Delta => {
+            let delta = PriceAccount::scale_value(amount, exponent, price_account_mut.exponent)
+                .map_err(|_| PriceError::InvalidPrice)?;
+
+            if delta == 0 && amount != 0 {
+                return Err(PriceError::InvalidPrice.into());
+            };
+
+            delta
+        }
+        PriceAdjustment::Bps => amount,
+    };
+    let price = price_account_mut.adjusted_price(adjustment, amount)?;
+    let confidence = PriceAccount::scale_confidence(confidence, exponent, price_account_mut.exponent)
+        .map_err(|_| PriceError::InvalidPrice)?;
+
+    apply_update(&mut price_account_mut, price_account, price_history, price, confidence, publish_slot, &clock)
 }
 
 // =================== TESTING process_modify_price =================== 
@@ -41,268 +73,220 @@ mod testing {
         account::Account, instruction::{AccountMeta, Instruction}, program_error::ProgramError, pubkey::Pubkey
     };
 
-    const PROGRAM_ID: Pubkey = solana_sdk::pubkey!("4zSrGy87rYtohmWK7PLBsojskZQa38GMwmoQkeK1nJSD");
-    const SIGNER: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceAdjustment, PriceHistory, PriceStatus, ZeroCopy},
+        test_utils::{mock_price_account, mock_price_history, mollusk, price_account_address, price_account_data, price_history_address, program_account, signer_account, AUTHORITY, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
 
-    #[test]
-    fn test_process_modify_price() {
-        let mollusk = Mollusk::new(&PROGRAM_ID, "target/deploy/pinocchio_price_account");
+    const NOT_AUTHORITY: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn trading_price_account() -> PriceAccount {
+        PriceAccount {
+            price: 10_000_000_000,
+            status: PriceStatus::Trading as u8,
+            ..mock_price_account(price_account_address(PRICE_ACCOUNT_ID).1)
+        }
+    }
 
-        let price_account_id = 13u64;
-        let price_to_set = 140.0f64; 
+    fn modify_price_instruction(signer: Pubkey, price_account_id: u64, adjustment: PriceAdjustment, amount: i64, confidence: u64) -> Instruction {
+        let (price_account_pda, _bump) = price_account_address(PRICE_ACCOUNT_ID);
+        let (price_history_pda, _bump) = price_history_address(&price_account_pda);
 
         let mut instruction_data = vec![];
         instruction_data.push(2u8);
         instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
-        instruction_data.extend_from_slice(&price_to_set.to_le_bytes());
-
-        let (price_account_pda, _bump) = Pubkey::find_program_address(
-            &[b"price_feed_account", price_account_id.to_le_bytes().as_ref()],
-            &PROGRAM_ID
-        );
-
-        let mock_price_account_data = {
-            let mut data = vec![];
-            data.extend_from_slice(&price_account_id.to_le_bytes()); // price_account_id
-            data.extend_from_slice(&0.0f64.to_le_bytes()); // initial price
-            data.extend_from_slice(&0i64.to_le_bytes()); // initial timestamp
-            data
-        };
+        instruction_data.push(adjustment as u8);
+        instruction_data.extend_from_slice(&amount.to_le_bytes());
+        instruction_data.extend_from_slice(&confidence.to_le_bytes());
+        instruction_data.extend_from_slice(&(-8i32).to_le_bytes());
+        instruction_data.extend_from_slice(&0u64.to_le_bytes());
 
-        let (system_program_id, system_account) = program::keyed_account_for_system_program();
-
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ID,
             accounts: vec![
-                AccountMeta::new(SIGNER, true),
+                AccountMeta::new(signer, true),
                 AccountMeta::new(price_account_pda, false),
-                AccountMeta::new(system_program_id, false),
+                AccountMeta::new(solana_sdk::system_program::id(), false),
+                AccountMeta::new(price_history_pda, false),
             ],
             data: instruction_data
-        };
+        }
+    }
 
-        let signer_account = Account {
-            lamports: 10_000_000,
-            data: vec![],
-            owner: solana_sdk::system_program::id(),
-            executable: false,
-            rent_epoch: 0,
-        };
+    fn setup(signer: Pubkey, price_account: PriceAccount) -> (Mollusk, Pubkey, Pubkey, Vec<(Pubkey, Account)>) {
+        let (price_account_pda, _bump) = price_account_address(PRICE_ACCOUNT_ID);
+        let (price_history_pda, price_history) = mock_price_history(&price_account_pda);
 
-        let price_account = Account {
-            lamports: 1_000_000, 
-            data: mock_price_account_data, 
-            owner: PROGRAM_ID, 
-            executable: false,
-            rent_epoch: 0,
-        };
+        let accounts = vec![
+            (signer, signer_account()),
+            (price_account_pda, program_account(price_account_data(&price_account))),
+            program::keyed_account_for_system_program(),
+            (price_history_pda, price_history),
+        ];
+
+        (mollusk(), price_account_pda, price_history_pda, accounts)
+    }
+
+    #[test]
+    fn test_process_modify_price() {
+        let (mollusk, price_account_pda, _price_history_pda, accounts) = setup(AUTHORITY, trading_price_account());
+
+        let result = mollusk.process_and_validate_instruction(
+            &modify_price_instruction(AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Delta, 4_000_000_000, 7_000_000),
+            &accounts,
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.price, 14_000_000_000);
+        assert_eq!(updated.confidence, 7_000_000);
+    }
+
+    #[test]
+    fn test_process_modify_price_delta_below_feed_precision() {
+        let (mollusk, _price_account_pda, _price_history_pda, accounts) = setup(AUTHORITY, trading_price_account());
+
+        let mut instruction = modify_price_instruction(AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Delta, 99, 7_000_000);
+        instruction.data[26..30].copy_from_slice(&(-10i32).to_le_bytes());
 
         mollusk.process_and_validate_instruction(
             &instruction,
-            &vec![
-                (SIGNER, signer_account),
-                (price_account_pda, price_account),
-                (system_program_id, system_account),
-            ],
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPrice as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_modify_price_unauthorized_signer() {
+        let (mollusk, _price_account_pda, _price_history_pda, accounts) = setup(NOT_AUTHORITY, trading_price_account());
+
+        mollusk.process_and_validate_instruction(
+            &modify_price_instruction(NOT_AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Delta, 4_000_000_000, 7_000_000),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_modify_price_insufficient_instruction_data() {
+        let (mollusk, _price_account_pda, _price_history_pda, accounts) = setup(AUTHORITY, trading_price_account());
+
+        let mut instruction = modify_price_instruction(AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Delta, 4_000_000_000, 7_000_000);
+        instruction.data.pop();
+
+        mollusk.process_and_validate_instruction(
+            &instruction,
+            &accounts,
+            &[Check::err(ProgramError::InvalidInstructionData)],
+        );
+    }
+
+    #[test]
+    fn test_process_modify_price_account_not_owned_by_program() {
+        let (mollusk, _price_account_pda, _price_history_pda, mut accounts) = setup(AUTHORITY, trading_price_account());
+
+        accounts[1].1.owner = solana_sdk::system_program::id();
+
+        mollusk.process_and_validate_instruction(
+            &modify_price_instruction(AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Delta, 4_000_000_000, 7_000_000),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAccountOwner as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_modify_price_with_negative_value() {
+        let (mollusk, _price_account_pda, _price_history_pda, accounts) = setup(AUTHORITY, trading_price_account());
+
+        mollusk.process_and_validate_instruction(
+            &modify_price_instruction(AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Delta, -15_000_000_000, 0),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::NegativePrice as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_modify_price_compute_units() {
+        let (mollusk, _price_account_pda, _price_history_pda, accounts) = setup(AUTHORITY, trading_price_account());
+
+        let result = mollusk.process_and_validate_instruction(
+            &modify_price_instruction(AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Delta, 4_000_000_000, 7_000_000),
+            &accounts,
             &[Check::success()],
         );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_process_modify_price_bps() {
+        let (mollusk, price_account_pda, price_history_pda, accounts) = setup(AUTHORITY, trading_price_account());
+
+        let result = mollusk.process_and_validate_instruction(
+            &modify_price_instruction(AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Bps, -250, 7_000_000),
+            &accounts,
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.price, 9_750_000_000);
+
+        let price_history = PriceHistory::load(&result.get_account(&price_history_pda).unwrap().data).unwrap();
+        assert_eq!(price_history.latest().unwrap().price, 9_750_000_000);
+    }
+
+    #[test]
+    fn test_process_modify_price_wrong_pda() {
+        let (mollusk, _price_account_pda, _price_history_pda, accounts) = setup(AUTHORITY, trading_price_account());
+
+        mollusk.process_and_validate_instruction(
+            &modify_price_instruction(AUTHORITY, 14, PriceAdjustment::Delta, 1, 7_000_000),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPriceAccountAddress as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_modify_price_overflow() {
+        let (mollusk, _price_account_pda, _price_history_pda, accounts) = setup(AUTHORITY, trading_price_account());
+
+        mollusk.process_and_validate_instruction(
+            &modify_price_instruction(AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Delta, i64::MAX, 7_000_000),
+            &accounts,
+            &[Check::err(ProgramError::ArithmeticOverflow)],
+        );
     }
 
-    // #[test]
-    // fn test_process_modify_price_insufficient_instruction_data() {
-    //     let mollusk = Mollusk::new(&PROGRAM_ID, "target/deploy/pinocchio_price_account");
-
-    //     let price_account_id = 13u64;
-
-    //     // Only 15 bytes instead of required 16 (missing 1 byte for f64)
-    //     let mut instruction_data = vec![];
-    //     instruction_data.push(2u8); // ModifyPrice instruction
-    //     instruction_data.extend_from_slice(&price_account_id.to_le_bytes()); // 8 bytes
-    //     instruction_data.extend_from_slice(&[100, 0, 0, 0, 0, 0, 0]); // Only 7 bytes instead of 8
-
-    //     let (price_account_pda, _bump) = Pubkey::find_program_address(
-    //         &[b"price_feed_account", price_account_id.to_le_bytes().as_ref()],
-    //         &PROGRAM_ID
-    //     );
-
-    //     let mock_price_account_data = {
-    //         let mut data = vec![];
-    //         data.extend_from_slice(&price_account_id.to_le_bytes());
-    //         data.extend_from_slice(&50.0f64.to_le_bytes()); // existing price
-    //         data.extend_from_slice(&1234567890i64.to_le_bytes()); // existing timestamp
-    //         data
-    //     };
-
-    //     let (system_program_id, system_account) = program::keyed_account_for_system_program();
-
-    //     let instruction = Instruction {
-    //         program_id: PROGRAM_ID,
-    //         accounts: vec![
-    //             AccountMeta::new(SIGNER, true),
-    //             AccountMeta::new(price_account_pda, false),
-    //             AccountMeta::new(system_program_id, false),
-    //         ],
-    //         data: instruction_data
-    //     };
-
-    //     let signer_account = Account {
-    //         lamports: 10_000_000,
-    //         data: vec![],
-    //         owner: solana_sdk::system_program::id(),
-    //         executable: false,
-    //         rent_epoch: 0,
-    //     };
-
-    //     let price_account = Account {
-    //         lamports: 1_000_000,
-    //         data: mock_price_account_data,
-    //         owner: PROGRAM_ID,
-    //         executable: false,
-    //         rent_epoch: 0,
-    //     };
-
-    //     mollusk.process_and_validate_instruction(
-    //         &instruction,
-    //         &vec![
-    //             (SIGNER, signer_account),
-    //             (price_account_pda, price_account),
-    //             (system_program_id, system_account),
-    //         ],
-    //         &[Check::err(ProgramError::InvalidInstructionData)],
-    //     );
-    // }
-
-    // #[test]
-    // fn test_process_modify_price_account_not_owned_by_program() {
-    //     let mollusk = Mollusk::new(&PROGRAM_ID, "target/deploy/pinocchio_price_account");
-
-    //     let price_account_id = 13u64;
-    //     let price_to_modify = 200.0f64;
-
-    //     let mut instruction_data = vec![];
-    //     instruction_data.push(2u8); // ModifyPrice instruction
-    //     instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
-    //     instruction_data.extend_from_slice(&price_to_modify.to_le_bytes());
-
-    //     let (price_account_pda, _bump) = Pubkey::find_program_address(
-    //         &[b"price_feed_account", price_account_id.to_le_bytes().as_ref()],
-    //         &PROGRAM_ID
-    //     );
-
-    //     let mock_price_account_data = {
-    //         let mut data = vec![];
-    //         data.extend_from_slice(&price_account_id.to_le_bytes());
-    //         data.extend_from_slice(&75.0f64.to_le_bytes()); // existing price
-    //         data.extend_from_slice(&1234567890i64.to_le_bytes()); // existing timestamp
-    //         data
-    //     };
-
-    //     let (system_program_id, system_account) = program::keyed_account_for_system_program();
-
-    //     let instruction = Instruction {
-    //         program_id: PROGRAM_ID,
-    //         accounts: vec![
-    //             AccountMeta::new(SIGNER, true),
-    //             AccountMeta::new(price_account_pda, false),
-    //             AccountMeta::new(system_program_id, false),
-    //         ],
-    //         data: instruction_data
-    //     };
-
-    //     let signer_account = Account {
-    //         lamports: 10_000_000,
-    //         data: vec![],
-    //         owner: solana_sdk::system_program::id(),
-    //         executable: false,
-    //         rent_epoch: 0,
-    //     };
-
-    //     // Price account owned by system program instead of our program
-    //     // This simulates trying to modify an account that wasn't properly initialized
-    //     let price_account = Account {
-    //         lamports: 1_000_000,
-    //         data: mock_price_account_data,
-    //         owner: solana_sdk::system_program::id(), // Wrong owner!
-    //         executable: false,
-    //         rent_epoch: 0,
-    //     };
-
-    //     // This should fail because PriceAccount::from_account_info_mut() 
-    //     // will likely fail when the account isn't owned by our program
-    //     mollusk.process_and_validate_instruction(
-    //         &instruction,
-    //         &vec![
-    //             (SIGNER, signer_account),
-    //             (price_account_pda, price_account),
-    //             (system_program_id, system_account),
-    //         ],
-    //         &[Check::err(ProgramError::InvalidAccountOwner)], // or whatever error your from_account_info_mut returns
-    //     );
-    // }
-
-    // #[test]
-    // fn test_process_modify_price_with_negative_value() {
-    //     let mollusk = Mollusk::new(&PROGRAM_ID, "target/deploy/pinocchio_price_account");
-
-    //     let price_account_id = 13u64;
-    //     let negative_price = -50.0f64; // Test with negative price
-
-    //     let mut instruction_data = vec![];
-    //     instruction_data.push(2u8); // ModifyPrice instruction
-    //     instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
-    //     instruction_data.extend_from_slice(&negative_price.to_le_bytes());
-
-    //     let (price_account_pda, _bump) = Pubkey::find_program_address(
-    //         &[b"price_feed_account", price_account_id.to_le_bytes().as_ref()],
-    //         &PROGRAM_ID
-    //     );
-
-    //     let mock_price_account_data = {
-    //         let mut data = vec![];
-    //         data.extend_from_slice(&price_account_id.to_le_bytes());
-    //         data.extend_from_slice(&100.0f64.to_le_bytes()); // existing price
-    //         data.extend_from_slice(&1234567890i64.to_le_bytes()); // existing timestamp
-    //         data
-    //     };
-
-    //     let (system_program_id, system_account) = program::keyed_account_for_system_program();
-
-    //     let instruction = Instruction {
-    //         program_id: PROGRAM_ID,
-    //         accounts: vec![
-    //             AccountMeta::new(SIGNER, true),
-    //             AccountMeta::new(price_account_pda, false),
-    //             AccountMeta::new(system_program_id, false),
-    //         ],
-    //         data: instruction_data
-    //     };
-
-    //     let signer_account = Account {
-    //         lamports: 10_000_000,
-    //         data: vec![],
-    //         owner: solana_sdk::system_program::id(),
-    //         executable: false,
-    //         rent_epoch: 0,
-    //     };
-
-    //     let price_account = Account {
-    //         lamports: 1_000_000,
-    //         data: mock_price_account_data,
-    //         owner: PROGRAM_ID,
-    //         executable: false,
-    //         rent_epoch: 0,
-    //     };
-
-    //     // This tests whether your program accepts negative prices
-    //     // You might want to add validation to reject negative prices if that's not allowed
-    //     mollusk.process_and_validate_instruction(
-    //         &instruction,
-    //         &vec![
-    //             (SIGNER, signer_account),
-    //             (price_account_pda, price_account),
-    //             (system_program_id, system_account),
-    //         ],
-    //         &[Check::success()], // Change to Check::err() if you want to reject negative prices
-    //     );
-    // }
-}
\ No newline at end of file
+    #[test]
+    fn test_process_modify_price_without_base_price() {
+        let unpublished = PriceAccount {
+            price: 0,
+            status: PriceStatus::Unknown as u8,
+            ..trading_price_account()
+        };
+
+        let (mollusk, _price_account_pda, _price_history_pda, accounts) = setup(AUTHORITY, unpublished);
+
+        mollusk.process_and_validate_instruction(
+            &modify_price_instruction(AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Delta, 1, 7_000_000),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::NoBasePrice as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_modify_price_invalid_adjustment() {
+        let (mollusk, _price_account_pda, _price_history_pda, accounts) = setup(AUTHORITY, trading_price_account());
+
+        let mut instruction = modify_price_instruction(AUTHORITY, PRICE_ACCOUNT_ID, PriceAdjustment::Delta, 1, 7_000_000);
+        instruction.data[9] = 2;
+
+        mollusk.process_and_validate_instruction(
+            &instruction,
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAdjustment as u32))],
+        );
+    }
+}
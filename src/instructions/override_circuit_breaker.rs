@@ -0,0 +1,182 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use super::price_update::load_for_authority;
+use crate::errors::PriceError;
+
+/// Lets the next price recorded on the feed skip the `max_deviation_bps` check, e.g. after a
+/// genuine market gap. The override is consumed by that update, whether it comes from
+/// `SetPrice`, `ModifyPrice` or a `SubmitPrice` that produces a new aggregate.
+pub fn process_override_circuit_breaker(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 8 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [authority, price_account] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let mut price_account_mut = load_for_authority(authority, price_account, price_account_id)?;
+
+    price_account_mut.deviation_override = 1;
+
+    Ok(())
+}
+
+// =================== TESTING process_override_circuit_breaker ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{program, Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceStatus, ZeroCopy},
+        test_utils::{mock_price_account, mock_price_history, mollusk, price_account_address, price_account_data, program_account, signer_account, AUTHORITY, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const NOT_AUTHORITY: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn mock_price_account_data(bump: u8) -> Vec<u8> {
+        price_account_data(&PriceAccount {
+            status: PriceStatus::Trading as u8,
+            price: 10_000_000_000,
+            max_deviation_bps: 1_000,
+            deviation_window_slots: 150,
+            ..mock_price_account(bump)
+        })
+    }
+
+    fn override_instruction(signer: Pubkey, price_account_pda: Pubkey, price_account_id: u64) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(10u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(price_account_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn set_price_instruction(price_account_pda: Pubkey, price_history_pda: Pubkey, system_program_id: Pubkey, price_account_id: u64, price: i64) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(1u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+        instruction_data.extend_from_slice(&price.to_le_bytes());
+        instruction_data.extend_from_slice(&((price / 1_000) as u64).to_le_bytes());
+        instruction_data.extend_from_slice(&(-8i32).to_le_bytes());
+        instruction_data.extend_from_slice(&0u64.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(AUTHORITY, true),
+                AccountMeta::new(price_account_pda, false),
+                AccountMeta::new(system_program_id, false),
+                AccountMeta::new(price_history_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn setup() -> (Mollusk, u64, Pubkey, Account) {
+        let (price_account_pda, bump) = price_account_address(PRICE_ACCOUNT_ID);
+
+        (mollusk(), PRICE_ACCOUNT_ID, price_account_pda, program_account(mock_price_account_data(bump)))
+    }
+
+    #[test]
+    fn test_process_override_circuit_breaker() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &override_instruction(AUTHORITY, price_account_pda, price_account_id),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.deviation_override, 1);
+    }
+
+    #[test]
+    fn test_process_override_circuit_breaker_allows_one_update() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+        let (price_history_pda, price_history) = mock_price_history(&price_account_pda);
+        let (system_program_id, system_account) = program::keyed_account_for_system_program();
+
+        let accounts = [
+            (AUTHORITY, signer_account()),
+            (price_account_pda, price_account),
+            (system_program_id, system_account),
+            (price_history_pda, price_history),
+        ];
+
+        mollusk.process_and_validate_instruction(
+            &set_price_instruction(price_account_pda, price_history_pda, system_program_id, price_account_id, 20_000_000_000),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::DeviationTooLarge as u32))],
+        );
+
+        let result = mollusk.process_and_validate_instruction_chain(
+            &[
+                (&override_instruction(AUTHORITY, price_account_pda, price_account_id), &[Check::success()]),
+                (&set_price_instruction(price_account_pda, price_history_pda, system_program_id, price_account_id, 20_000_000_000), &[Check::success()]),
+                (&set_price_instruction(price_account_pda, price_history_pda, system_program_id, price_account_id, 40_000_000_000), &[Check::err(ProgramError::Custom(PriceError::DeviationTooLarge as u32))]),
+            ],
+            &accounts,
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.price, 20_000_000_000);
+        assert_eq!(updated.deviation_override, 0);
+    }
+
+    #[test]
+    fn test_process_override_circuit_breaker_not_authority() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &override_instruction(NOT_AUTHORITY, price_account_pda, price_account_id),
+            &[
+                (NOT_AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_override_circuit_breaker_compute_units() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &override_instruction(AUTHORITY, price_account_pda, price_account_id),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
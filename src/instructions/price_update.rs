@@ -0,0 +1,116 @@
+use pinocchio::{account_info::{AccountInfo, RefMut}, program_error::ProgramError, sysvars::clock::Clock, ProgramResult};
+
+use crate::{errors::PriceError, states::{PriceAccount, PriceHistory, PriceHistoryEntry, ZeroCopy}};
+
+/// Borrows the feed for an instruction signed by `signer`: signer, owner, header and feed PDA.
+pub(crate) fn load_signed<'a>(
+    signer: &AccountInfo,
+    price_account: &'a AccountInfo,
+    price_account_id: u64,
+) -> Result<RefMut<'a, PriceAccount>, ProgramError> {
+    if !signer.is_signer() {
+        return Err(PriceError::MissingRequiredSignature.into());
+    };
+
+    let price_account_mut = PriceAccount::from_account_info_mut(price_account)?;
+    price_account_mut.verify_address(price_account.key(), price_account_id)?;
+
+    Ok(price_account_mut)
+}
+
+/// Account checks shared by the feed authority's instructions: `load_signed`, then that
+/// `authority` is the feed's authority.
+pub(crate) fn load_for_authority<'a>(
+    authority: &AccountInfo,
+    price_account: &'a AccountInfo,
+    price_account_id: u64,
+) -> Result<RefMut<'a, PriceAccount>, ProgramError> {
+    let price_account_mut = load_signed(authority, price_account, price_account_id)?;
+
+    if price_account_mut.authority != *authority.key() {
+        return Err(PriceError::InvalidAuthority.into());
+    };
+
+    Ok(price_account_mut)
+}
+
+/// Account checks shared by `SetPrice` and `ModifyPrice`: `load_for_authority` and
+/// publish-slot ordering. `publish_slot` may not be in the future nor older than the slot
+/// reported by the previous update.
+pub(crate) fn load_for_update<'a>(
+    signer: &AccountInfo,
+    price_account: &'a AccountInfo,
+    price_account_id: u64,
+    publish_slot: u64,
+    clock: &Clock,
+) -> Result<RefMut<'a, PriceAccount>, ProgramError> {
+    let price_account_mut = load_for_authority(signer, price_account, price_account_id)?;
+
+    if publish_slot > clock.slot {
+        return Err(PriceError::InvalidPublishSlot.into());
+    };
+
+    if publish_slot < price_account_mut.last_publish_slot {
+        return Err(PriceError::StaleUpdate.into());
+    };
+
+    Ok(price_account_mut)
+}
+
+/// Validates `price` and `confidence`, both at the feed exponent, against the feed's rules,
+/// then records them with their `publish_slot` and appends them to the price history.
+pub(crate) fn apply_update(
+    price_account_mut: &mut PriceAccount,
+    price_account: &AccountInfo,
+    price_history: &AccountInfo,
+    price: i64,
+    confidence: u64,
+    publish_slot: u64,
+    clock: &Clock,
+) -> ProgramResult {
+    price_account_mut.validate_price(price)?;
+
+    if !price_account_mut.confidence_within_limit(price, confidence) {
+        return Err(PriceError::ConfidenceTooWide.into());
+    };
+
+    check_circuit_breaker(price_account_mut, price, clock.slot)?;
+
+    price_account_mut.record_update(price, confidence, clock.unix_timestamp, clock.slot);
+    price_account_mut.last_publish_slot = publish_slot;
+
+    append_history(price_account_mut, price_account, price_history, price, confidence, clock)
+}
+
+/// Consumes a pending circuit-breaker override, or fails with `DeviationTooLarge` if `price`
+/// moves too far from the current price.
+pub(crate) fn check_circuit_breaker(price_account_mut: &mut PriceAccount, price: i64, slot: u64) -> ProgramResult {
+    if price_account_mut.deviation_override != 0 {
+        price_account_mut.deviation_override = 0;
+    } else {
+        price_account_mut.check_deviation(price, slot)?;
+    };
+
+    Ok(())
+}
+
+/// Appends a newly recorded feed price to the feed's price history.
+pub(crate) fn append_history(
+    price_account_mut: &PriceAccount,
+    price_account: &AccountInfo,
+    price_history: &AccountInfo,
+    price: i64,
+    confidence: u64,
+    clock: &Clock,
+) -> ProgramResult {
+    let mut price_history_mut = PriceHistory::from_account_info_mut(price_history)?;
+    price_history_mut.verify_feed(price_account.key(), price_account_mut.price_history_bump)?;
+    price_history_mut.append(PriceHistoryEntry {
+        price,
+        confidence,
+        timestamp: clock.unix_timestamp,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
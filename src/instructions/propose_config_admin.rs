@@ -0,0 +1,146 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+
+use crate::{errors::PriceError, states::{Config, ZeroCopy}};
+
+/// Writes `new_admin` into the config's `pending_admin`. The transfer only takes effect once the
+/// proposed key signs `AcceptConfigAdmin`; proposing the zero key cancels a pending transfer.
+pub fn process_propose_config_admin(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 32 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [admin, config] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let new_admin: Pubkey = instruction_data[0..32].try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    if !admin.is_signer() {
+        return Err(PriceError::MissingRequiredSignature.into());
+    };
+
+    let mut config_mut = Config::from_account_info_mut(config)?;
+    config_mut.verify_address(config.key())?;
+
+    if config_mut.admin != *admin.key() {
+        return Err(PriceError::InvalidConfigAdmin.into());
+    };
+
+    config_mut.pending_admin = new_admin;
+
+    Ok(())
+}
+
+// =================== TESTING process_propose_config_admin ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::result::Check;
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{Config, ZeroCopy},
+        test_utils::{mock_config, mollusk, signer_account, PROGRAM_ID},
+    };
+
+    const ADMIN: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const NEW_ADMIN: Pubkey = Pubkey::new_from_array([3u8; 32]);
+    const NOT_ADMIN: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn propose_config_admin_instruction(signer: Pubkey, config_pda: Pubkey, new_admin: &Pubkey) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(17u8);
+        instruction_data.extend_from_slice(new_admin.as_ref());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(config_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    #[test]
+    fn test_process_propose_config_admin() {
+        let mollusk = mollusk();
+
+        let (config_pda, config_account) = mock_config(ADMIN, 0);
+
+        let result = mollusk.process_and_validate_instruction(
+            &propose_config_admin_instruction(ADMIN, config_pda, &NEW_ADMIN),
+            &[
+                (ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+            &[Check::success()],
+        );
+
+        let updated = Config::load(&result.get_account(&config_pda).unwrap().data).unwrap();
+        assert_eq!(updated.admin, ADMIN.to_bytes());
+        assert_eq!(updated.pending_admin, NEW_ADMIN.to_bytes());
+    }
+
+    #[test]
+    fn test_process_propose_config_admin_cancel() {
+        let mollusk = mollusk();
+
+        let (config_pda, config_account) = mock_config(ADMIN, 0);
+
+        let result = mollusk.process_and_validate_instruction_chain(
+            &[
+                (&propose_config_admin_instruction(ADMIN, config_pda, &NEW_ADMIN), &[Check::success()]),
+                (&propose_config_admin_instruction(ADMIN, config_pda, &Pubkey::default()), &[Check::success()]),
+            ],
+            &[
+                (ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+        );
+
+        let updated = Config::load(&result.get_account(&config_pda).unwrap().data).unwrap();
+        assert_eq!(updated.pending_admin, [0u8; 32]);
+    }
+
+    #[test]
+    fn test_process_propose_config_admin_not_admin() {
+        let mollusk = mollusk();
+
+        let (config_pda, config_account) = mock_config(ADMIN, 0);
+
+        mollusk.process_and_validate_instruction(
+            &propose_config_admin_instruction(NOT_ADMIN, config_pda, &NOT_ADMIN),
+            &[
+                (NOT_ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidConfigAdmin as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_propose_config_admin_compute_units() {
+        let mollusk = mollusk();
+
+        let (config_pda, config_account) = mock_config(ADMIN, 0);
+
+        let result = mollusk.process_and_validate_instruction(
+            &propose_config_admin_instruction(ADMIN, config_pda, &NEW_ADMIN),
+            &[
+                (ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
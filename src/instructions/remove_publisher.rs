@@ -0,0 +1,179 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::{self, Pubkey}, ProgramResult};
+use pinocchio_log::log;
+
+use super::price_update::load_for_authority;
+use crate::{errors::PriceError, states::PublisherSlot};
+
+/// Revokes `publisher`. The last occupied slot is moved into the freed one so occupied slots
+/// stay contiguous. The aggregate stops updating if fewer than `min_publishers` remain.
+pub fn process_remove_publisher(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 40 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [authority, price_account] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let publisher: Pubkey = instruction_data[8..40].try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let mut price_account_mut = load_for_authority(authority, price_account, price_account_id)?;
+
+    let index = price_account_mut
+        .publisher_index(&publisher)
+        .ok_or(PriceError::UnknownPublisher)?;
+
+    let last = price_account_mut.num_publishers as usize - 1;
+    price_account_mut.publishers[index] = price_account_mut.publishers[last];
+    price_account_mut.publishers[last] = PublisherSlot::default();
+    price_account_mut.num_publishers -= 1;
+
+    log!("Publisher removed from slot {} ({} in use)", index, price_account_mut.num_publishers);
+    pubkey::log(&publisher);
+
+    Ok(())
+}
+
+// =================== TESTING process_remove_publisher ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PublisherSlot, ZeroCopy},
+        test_utils::{mock_price_account, mollusk, price_account_address, price_account_data, program_account, signer_account, AUTHORITY, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const NOT_AUTHORITY: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const PUBLISHERS: [Pubkey; 3] = [
+        Pubkey::new_from_array([11u8; 32]),
+        Pubkey::new_from_array([12u8; 32]),
+        Pubkey::new_from_array([13u8; 32]),
+    ];
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn mock_price_account_data(bump: u8) -> Vec<u8> {
+        let mut price_account = PriceAccount {
+            num_publishers: PUBLISHERS.len() as u8,
+            min_publishers: 1,
+            ..mock_price_account(bump)
+        };
+
+        for (index, (slot, publisher)) in price_account.publishers.iter_mut().zip(PUBLISHERS.iter()).enumerate() {
+            *slot = PublisherSlot {
+                publisher: publisher.to_bytes(),
+                price: 100 + index as i64,
+                confidence: 1,
+                publish_slot: 1,
+            };
+        }
+
+        price_account_data(&price_account)
+    }
+
+    fn remove_publisher_instruction(signer: Pubkey, price_account_pda: Pubkey, price_account_id: u64, publisher: &Pubkey) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(8u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+        instruction_data.extend_from_slice(publisher.as_ref());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(price_account_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn setup() -> (Mollusk, u64, Pubkey, Account) {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(bump));
+
+        (mollusk, price_account_id, price_account_pda, price_account)
+    }
+
+    #[test]
+    fn test_process_remove_publisher() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &remove_publisher_instruction(AUTHORITY, price_account_pda, price_account_id, &PUBLISHERS[0]),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.num_publishers, 2);
+        assert_eq!(updated.publishers[0].publisher, PUBLISHERS[2].to_bytes());
+        assert_eq!(updated.publishers[0].price, 102);
+        assert_eq!(updated.publishers[1].publisher, PUBLISHERS[1].to_bytes());
+        assert_eq!(updated.publishers[2], PublisherSlot::default());
+    }
+
+    #[test]
+    fn test_process_remove_publisher_unknown() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &remove_publisher_instruction(AUTHORITY, price_account_pda, price_account_id, &NOT_AUTHORITY),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::UnknownPublisher as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_remove_publisher_not_authority() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &remove_publisher_instruction(NOT_AUTHORITY, price_account_pda, price_account_id, &PUBLISHERS[0]),
+            &[
+                (NOT_AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_remove_publisher_compute_units() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &remove_publisher_instruction(AUTHORITY, price_account_pda, price_account_id, &PUBLISHERS[1]),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
@@ -1,119 +1,321 @@
-use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, sysvars::{clock::Clock, rent::Rent, Sysvar}, *};
-use pinocchio_system::instructions::CreateAccount;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, ProgramResult};
 
-use crate::states::{price_account, PriceAccount};
+use super::price_update::{apply_update, load_for_update};
+use crate::{errors::PriceError, states::PriceAccount};
 
+/// Instruction data is `price_account_id: u64`, `price: i64`, `confidence: u64`,
+/// `exponent: i32` and `publish_slot: u64`. Replaces the feed price with `price`. When
+/// `exponent` is finer than the feed exponent, the price is truncated toward zero and the
+/// confidence rounded up.
 pub fn process_set_price(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
-    if instruction_data.len() < 16 {
+    if instruction_data.len() < 36 {
         return Err(ProgramError::InvalidInstructionData);
     }
 
-    let [signer, price_account, system_program] = accounts else {
-        return Err(ProgramError::InvalidAccountData);
+    let [signer, price_account, _system_program, price_history] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
     };
 
     let price_account_id = u64::from_le_bytes(
         instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    let price_to_set = f64::from_le_bytes(
+    let price_to_set = i64::from_le_bytes(
         instruction_data[8..16].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    let (price_account_pda, _bump) = pubkey::find_program_address(
-        &[b"price_feed_account", price_account_id.to_le_bytes().as_ref()],
-        &crate::ID
+    let confidence = u64::from_le_bytes(
+        instruction_data[16..24].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    if !signer.is_signer() || price_account.data_len() == 0 || *price_account.key() != price_account_pda {
-        return Err(ProgramError::InvalidAccountData);
-    };
+    let exponent = i32::from_le_bytes(
+        instruction_data[24..28].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let publish_slot = u64::from_le_bytes(
+        instruction_data[28..36].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
 
-    let mut price_account_mut = PriceAccount::from_account_info_mut(price_account)?;
-    price_account_mut.price = price_to_set;
-    price_account_mut.last_updated_timestamp = Clock::get()?.unix_timestamp;
+    let clock = Clock::get()?;
 
-    Ok(())
+    let mut price_account_mut = load_for_update(signer, price_account, price_account_id, publish_slot, &clock)?;
+
+    let price = PriceAccount::scale_value(price_to_set, exponent, price_account_mut.exponent)
+        .map_err(|_| PriceError::InvalidPrice)?;
+    let confidence = PriceAccount::scale_confidence(confidence, exponent, price_account_mut.exponent)
+        .map_err(|_| PriceError::InvalidPrice)?;
+
+    apply_update(&mut price_account_mut, price_account, price_history, price, confidence, publish_slot, &clock)
 }
 
 // =================== TESTING process_set_price =================== 
 
 #[cfg(test)]
 mod tests {
-    use mollusk_svm::{program, Mollusk, result::Check};
+    use mollusk_svm::{program, result::Check};
     use solana_sdk::{
         account::Account,
         instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
         pubkey::Pubkey,
     };
 
-    const PROGRAM_ID: Pubkey = solana_sdk::pubkey!("4zSrGy87rYtohmWK7PLBsojskZQa38GMwmoQkeK1nJSD");
-    const SIGNER: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceHistory, ZeroCopy},
+        test_utils::{mock_price_account, mock_price_account_data, mock_price_history, mollusk, price_account_address, price_account_data, price_history_address, program_account, signer_account, AUTHORITY, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
 
-    #[test]
-    fn test_process_set_price() {
-        let mollusk = Mollusk::new(&PROGRAM_ID, "target/deploy/pinocchio_price_account");
-        
-        let price_account_id = 13u64;
-        let price_to_set = 100.0f64; 
+    const NOT_AUTHORITY: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
 
+    fn set_price_instruction(signer: Pubkey, price: i64, confidence: u64, exponent: i32, publish_slot: u64) -> Instruction {
         let mut instruction_data = vec![];
-        instruction_data.push(1u8); 
-        instruction_data.extend_from_slice(&price_account_id.to_le_bytes()); 
-        instruction_data.extend_from_slice(&price_to_set.to_le_bytes()); 
+        instruction_data.push(1u8);
+        instruction_data.extend_from_slice(&PRICE_ACCOUNT_ID.to_le_bytes());
+        instruction_data.extend_from_slice(&price.to_le_bytes());
+        instruction_data.extend_from_slice(&confidence.to_le_bytes());
+        instruction_data.extend_from_slice(&exponent.to_le_bytes());
+        instruction_data.extend_from_slice(&publish_slot.to_le_bytes());
 
-        let (price_account_pda, _bump) = Pubkey::find_program_address(
-            &[b"price_feed_account", price_account_id.to_le_bytes().as_ref()],
-            &PROGRAM_ID
-        );
+        let (price_account_pda, _bump) = price_account_address(PRICE_ACCOUNT_ID);
+        let (price_history_pda, _bump) = price_history_address(&price_account_pda);
 
-        let mock_price_account_data = {
-            let mut data = vec![];
-            data.extend_from_slice(&price_account_id.to_le_bytes()); // price_account_id
-            data.extend_from_slice(&0.0f64.to_le_bytes()); // initial price
-            data.extend_from_slice(&0i64.to_le_bytes()); // initial timestamp
-            data
-        };
-
-        let (system_program_id, system_account) = program::keyed_account_for_system_program();
-
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ID,
             accounts: vec![
-                AccountMeta::new(SIGNER, true),
+                AccountMeta::new(signer, true),
                 AccountMeta::new(price_account_pda, false),
-                AccountMeta::new(system_program_id, false),
+                AccountMeta::new(solana_sdk::system_program::id(), false),
+                AccountMeta::new(price_history_pda, false),
             ],
-            data: instruction_data
-        };
+            data: instruction_data,
+        }
+    }
+
+    fn accounts(signer: Pubkey, price_account: Account) -> Vec<(Pubkey, Account)> {
+        let (price_account_pda, _bump) = price_account_address(PRICE_ACCOUNT_ID);
+
+        vec![
+            (signer, signer_account()),
+            (price_account_pda, price_account),
+            program::keyed_account_for_system_program(),
+            mock_price_history(&price_account_pda),
+        ]
+    }
+
+    fn feed_account() -> Account {
+        program_account(mock_price_account_data(price_account_address(PRICE_ACCOUNT_ID).1))
+    }
+
+    #[test]
+    fn test_process_set_price() {
+        mollusk().process_and_validate_instruction(
+            &set_price_instruction(AUTHORITY, 10_000_000_000, 5_000_000, -8, 0),
+            &accounts(AUTHORITY, feed_account()),
+            &[Check::success()],
+        );
+    }
+
+    #[test]
+    fn test_process_set_price_unauthorized_signer() {
+        mollusk().process_and_validate_instruction(
+            &set_price_instruction(NOT_AUTHORITY, 10_000_000_000, 5_000_000, -8, 0),
+            &accounts(NOT_AUTHORITY, feed_account()),
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_set_price_rescales_to_feed_exponent() {
+        let mut mollusk = mollusk();
+        mollusk.warp_to_slot(42);
+
+        let (price_account_pda, _bump) = price_account_address(PRICE_ACCOUNT_ID);
+        let (price_history_pda, _bump) = price_history_address(&price_account_pda);
+
+        let result = mollusk.process_and_validate_instruction(
+            &set_price_instruction(AUTHORITY, 100, 0, 0, 0),
+            &accounts(AUTHORITY, feed_account()),
+            &[Check::success()],
+        );
+
+        let data = &result.get_account(&price_account_pda).unwrap().data;
+        let updated = PriceAccount::load(data).unwrap();
+        assert_eq!(updated.price, 10_000_000_000);
+        assert_eq!(updated.exponent, -8);
+        assert_eq!(updated.last_updated_slot, 42);
+
+        let price_history = PriceHistory::load(&result.get_account(&price_history_pda).unwrap().data).unwrap();
+        let latest = price_history.latest().unwrap();
+        assert_eq!(price_history.len, 1);
+        assert_eq!(latest.price, 10_000_000_000);
+        assert_eq!(latest.slot, 42);
+    }
+
+    #[test]
+    fn test_process_set_price_rounds_confidence_up_to_coarser_exponent() {
+        let (price_account_pda, _bump) = price_account_address(PRICE_ACCOUNT_ID);
 
-        let signer_account = Account {
-            lamports: 10_000_000,
-            data: vec![],
+        let result = mollusk().process_and_validate_instruction(
+            &set_price_instruction(AUTHORITY, 1_000_000_000_009, 500_000_001, -10, 0),
+            &accounts(AUTHORITY, feed_account()),
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.price, 10_000_000_000);
+        assert_eq!(updated.confidence, 5_000_001);
+    }
+
+    #[test]
+    fn test_process_set_price_confidence_too_wide() {
+        mollusk().process_and_validate_instruction(
+            &set_price_instruction(AUTHORITY, 10_000_000_000, 200_000_000, -8, 0),
+            &accounts(AUTHORITY, feed_account()),
+            &[Check::err(ProgramError::Custom(PriceError::ConfidenceTooWide as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_set_price_wrong_owner() {
+        let price_account = Account {
             owner: solana_sdk::system_program::id(),
-            executable: false,
-            rent_epoch: 0,
+            ..feed_account()
         };
 
-        let price_account = Account {
-            lamports: 1_000_000, 
-            data: mock_price_account_data, 
-            owner: PROGRAM_ID, 
-            executable: false,
-            rent_epoch: 0,
+        mollusk().process_and_validate_instruction(
+            &set_price_instruction(AUTHORITY, 10_000_000_000, 5_000_000, -8, 0),
+            &accounts(AUTHORITY, price_account),
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAccountOwner as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_set_price_foreign_price_history() {
+        let mut accounts = accounts(AUTHORITY, feed_account());
+        accounts[3].1 = mock_price_history(&Pubkey::new_unique()).1;
+
+        mollusk().process_and_validate_instruction(
+            &set_price_instruction(AUTHORITY, 10_000_000_000, 5_000_000, -8, 0),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPriceHistoryAddress as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_set_price_compute_units() {
+        let result = mollusk().process_and_validate_instruction(
+            &set_price_instruction(AUTHORITY, 10_000_000_000, 5_000_000, -8, 0),
+            &accounts(AUTHORITY, feed_account()),
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+
+    #[test]
+    fn test_process_set_price_older_publish_slot() {
+        let mut mollusk = mollusk();
+        mollusk.warp_to_slot(120);
+
+        let price_account = PriceAccount {
+            last_publish_slot: 100,
+            ..mock_price_account(price_account_address(PRICE_ACCOUNT_ID).1)
         };
 
         mollusk.process_and_validate_instruction(
-            &instruction,
-            &vec![
-                (SIGNER, signer_account),
-                (price_account_pda, price_account),
-                (system_program_id, system_account),
-            ],
+            &set_price_instruction(AUTHORITY, 10_000_000_000, 5_000_000, -8, 99),
+            &accounts(AUTHORITY, program_account(price_account_data(&price_account))),
+            &[Check::err(ProgramError::Custom(PriceError::StaleUpdate as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_set_price_future_publish_slot() {
+        let mut mollusk = mollusk();
+        mollusk.warp_to_slot(120);
+
+        mollusk.process_and_validate_instruction(
+            &set_price_instruction(AUTHORITY, 10_000_000_000, 5_000_000, -8, 121),
+            &accounts(AUTHORITY, feed_account()),
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPublishSlot as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_set_price_records_publish_slot() {
+        let mut mollusk = mollusk();
+        mollusk.warp_to_slot(120);
+
+        let price_account = PriceAccount {
+            last_updated_slot: 110,
+            last_publish_slot: 100,
+            ..mock_price_account(price_account_address(PRICE_ACCOUNT_ID).1)
+        };
+
+        let result = mollusk.process_and_validate_instruction(
+            &set_price_instruction(AUTHORITY, 10_000_000_000, 5_000_000, -8, 105),
+            &accounts(AUTHORITY, program_account(price_account_data(&price_account))),
             &[Check::success()],
         );
+
+        let (price_account_pda, _bump) = price_account_address(PRICE_ACCOUNT_ID);
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.last_publish_slot, 105);
+        assert_eq!(updated.last_updated_slot, 120);
+    }
+
+    fn process_set_price_with_rules(price_to_set: i64, allow_negative: u8, min_price: i64, max_price: i64, check: Check) {
+        let price_account = PriceAccount {
+            allow_negative,
+            min_price,
+            max_price,
+            ..mock_price_account(price_account_address(PRICE_ACCOUNT_ID).1)
+        };
+
+        mollusk().process_and_validate_instruction(
+            &set_price_instruction(AUTHORITY, price_to_set, 0, -8, 0),
+            &accounts(AUTHORITY, program_account(price_account_data(&price_account))),
+            &[check],
+        );
+    }
+
+    #[test]
+    fn test_process_set_price_rejects_negative_price() {
+        process_set_price_with_rules(
+            -10_000_000_000, 0, i64::MIN, i64::MAX,
+            Check::err(ProgramError::Custom(PriceError::NegativePrice as u32)),
+        );
+    }
+
+    #[test]
+    fn test_process_set_price_rejects_zero_price() {
+        process_set_price_with_rules(
+            0, 0, i64::MIN, i64::MAX,
+            Check::err(ProgramError::Custom(PriceError::ZeroPrice as u32)),
+        );
     }
 
+    #[test]
+    fn test_process_set_price_accepts_negative_price_when_allowed() {
+        process_set_price_with_rules(-10_000_000_000, 1, i64::MIN, i64::MAX, Check::success());
+    }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_process_set_price_below_minimum() {
+        process_set_price_with_rules(
+            10_000_000_000, 0, 20_000_000_000, i64::MAX,
+            Check::err(ProgramError::Custom(PriceError::PriceBelowMinimum as u32)),
+        );
+    }
+
+    #[test]
+    fn test_process_set_price_above_maximum() {
+        process_set_price_with_rules(
+            10_000_000_000, 0, i64::MIN, 5_000_000_000,
+            Check::err(ProgramError::Custom(PriceError::PriceAboveMaximum as u32)),
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use pinocchio_log::log;
+
+use super::price_update::load_for_authority;
+use crate::{errors::PriceError, states::PriceStatus};
+
+/// Sets the feed status: `Halted` to tell consumers to stop using the price and `Trading` to
+/// resume. Only available once the feed has published a price.
+pub fn process_set_status(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 9 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [authority, price_account] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let status = PriceStatus::try_from(instruction_data[8])?;
+
+    let mut price_account_mut = load_for_authority(authority, price_account, price_account_id)?;
+
+    price_account_mut.set_status(status)?;
+
+    log!("Feed status set to {}", status as u8);
+
+    Ok(())
+}
+
+// =================== TESTING process_set_status ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceStatus, ZeroCopy},
+        test_utils::{mock_price_account, mollusk, price_account_address, price_account_data, program_account, signer_account, AUTHORITY, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const NOT_AUTHORITY: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn mock_price_account_data(bump: u8) -> Vec<u8> {
+        price_account_data(&PriceAccount {
+            status: PriceStatus::Trading as u8,
+            ..mock_price_account(bump)
+        })
+    }
+
+    fn set_status_instruction(signer: Pubkey, price_account_pda: Pubkey, price_account_id: u64, status: u8) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(11u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+        instruction_data.push(status);
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(price_account_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn setup() -> (Mollusk, u64, Pubkey, Account) {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(bump));
+
+        (mollusk, price_account_id, price_account_pda, price_account)
+    }
+
+    #[test]
+    fn test_process_set_status() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &set_status_instruction(AUTHORITY, price_account_pda, price_account_id, PriceStatus::Halted as u8),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.status(), Ok(PriceStatus::Halted));
+    }
+
+    #[test]
+    fn test_process_set_status_invalid_status() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &set_status_instruction(AUTHORITY, price_account_pda, price_account_id, 3),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidStatus as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_set_status_unknown() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &set_status_instruction(AUTHORITY, price_account_pda, price_account_id, PriceStatus::Unknown as u8),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidStatusTransition as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_set_status_not_published() {
+        let mollusk = mollusk();
+
+        let (price_account_pda, bump) = price_account_address(PRICE_ACCOUNT_ID);
+
+        let price_account = program_account(price_account_data(&mock_price_account(bump)));
+
+        mollusk.process_and_validate_instruction(
+            &set_status_instruction(AUTHORITY, price_account_pda, PRICE_ACCOUNT_ID, PriceStatus::Trading as u8),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::PriceNotPublished as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_set_status_not_authority() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &set_status_instruction(NOT_AUTHORITY, price_account_pda, price_account_id, PriceStatus::Halted as u8),
+            &[
+                (NOT_AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_set_status_compute_units() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &set_status_instruction(AUTHORITY, price_account_pda, price_account_id, PriceStatus::Halted as u8),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
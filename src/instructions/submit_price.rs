@@ -0,0 +1,336 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, ProgramResult};
+use pinocchio_log::log;
+
+use super::price_update::{append_history, check_circuit_breaker};
+use crate::{errors::PriceError, states::{PriceAccount, PublisherSlot, ZeroCopy}};
+
+/// Records a publisher's price in its slot and, once `min_publishers` submissions are fresh,
+/// writes their median into the feed's aggregate `price` and `confidence` and appends it to
+/// the price history. The median is subject to the same circuit breaker as `SetPrice`; when it
+/// trips, the submission is still recorded but the aggregate and history are left unchanged.
+pub fn process_submit_price(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 36 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [publisher, price_account, price_history] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let submitted_price = i64::from_le_bytes(
+        instruction_data[8..16].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let confidence = u64::from_le_bytes(
+        instruction_data[16..24].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let exponent = i32::from_le_bytes(
+        instruction_data[24..28].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let publish_slot = u64::from_le_bytes(
+        instruction_data[28..36].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    if !publisher.is_signer() {
+        return Err(PriceError::MissingRequiredSignature.into());
+    };
+
+    let mut price_account_mut = PriceAccount::from_account_info_mut(price_account)?;
+    price_account_mut.verify_address(price_account.key(), price_account_id)?;
+
+    let index = price_account_mut
+        .publisher_index(publisher.key())
+        .ok_or(PriceError::UnknownPublisher)?;
+
+    let clock = Clock::get()?;
+
+    if publish_slot > clock.slot {
+        return Err(PriceError::InvalidPublishSlot.into());
+    };
+
+    if publish_slot < price_account_mut.publishers[index].publish_slot {
+        return Err(PriceError::StaleUpdate.into());
+    };
+
+    let price = PriceAccount::scale_value(submitted_price, exponent, price_account_mut.exponent)
+        .map_err(|_| PriceError::InvalidPrice)?;
+    let confidence = PriceAccount::scale_confidence(confidence, exponent, price_account_mut.exponent)
+        .map_err(|_| PriceError::InvalidPrice)?;
+
+    price_account_mut.validate_price(price)?;
+
+    if !price_account_mut.confidence_within_limit(price, confidence) {
+        return Err(PriceError::ConfidenceTooWide.into());
+    };
+
+    price_account_mut.publishers[index] = PublisherSlot {
+        publisher: *publisher.key(),
+        price,
+        confidence,
+        publish_slot,
+    };
+
+    if let Some((price, confidence)) = price_account_mut.aggregate(clock.slot) {
+        if check_circuit_breaker(&mut price_account_mut, price, clock.slot).is_err() {
+            log!("Aggregate for price account {} held by the circuit breaker", price_account_id);
+            return Ok(());
+        };
+
+        price_account_mut.record_update(price, confidence, clock.unix_timestamp, clock.slot);
+        append_history(&price_account_mut, price_account, price_history, price, confidence, &clock)?;
+    };
+
+    Ok(())
+}
+
+// =================== TESTING process_submit_price ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceHistory, PriceStatus, PublisherSlot, ZeroCopy},
+        test_utils::{mock_price_account, mock_price_history, mollusk, price_account_address, price_account_data, price_history_address, program_account, signer_account, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const PUBLISHERS: [Pubkey; 3] = [
+        Pubkey::new_from_array([11u8; 32]),
+        Pubkey::new_from_array([12u8; 32]),
+        Pubkey::new_from_array([13u8; 32]),
+    ];
+    const NOT_PUBLISHER: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const CURRENT_SLOT: u64 = 100;
+    const MAX_COMPUTE_UNITS: u64 = 4_000;
+
+    fn mock_price_account_data(min_publishers: u8, bump: u8) -> Vec<u8> {
+        let mut price_account = PriceAccount {
+            num_publishers: PUBLISHERS.len() as u8,
+            min_publishers,
+            ..mock_price_account(bump)
+        };
+
+        for (slot, publisher) in price_account.publishers.iter_mut().zip(PUBLISHERS.iter()) {
+            *slot = PublisherSlot {
+                publisher: publisher.to_bytes(),
+                ..Default::default()
+            };
+        }
+
+        price_account_data(&price_account)
+    }
+
+    fn submit_price_instruction(publisher: Pubkey, price_account_pda: Pubkey, price_account_id: u64, price: i64, publish_slot: u64) -> Instruction {
+        let (price_history_pda, _) = price_history_address(&price_account_pda);
+
+        let mut instruction_data = vec![];
+        instruction_data.push(6u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+        instruction_data.extend_from_slice(&price.to_le_bytes());
+        instruction_data.extend_from_slice(&((price / 1_000) as u64).to_le_bytes());
+        instruction_data.extend_from_slice(&(-8i32).to_le_bytes());
+        instruction_data.extend_from_slice(&publish_slot.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(publisher, true),
+                AccountMeta::new(price_account_pda, false),
+                AccountMeta::new(price_history_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn setup(min_publishers: u8) -> (Mollusk, u64, Pubkey, Account) {
+        let mut mollusk = mollusk();
+        mollusk.warp_to_slot(CURRENT_SLOT);
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(min_publishers, bump));
+
+        (mollusk, price_account_id, price_account_pda, price_account)
+    }
+
+    #[test]
+    fn test_process_submit_price() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(1);
+
+        let result = mollusk.process_and_validate_instruction(
+            &submit_price_instruction(PUBLISHERS[1], price_account_pda, price_account_id, 10_000_000_000, CURRENT_SLOT),
+            &[
+                (PUBLISHERS[1], signer_account()),
+                (price_account_pda, price_account),
+                mock_price_history(&price_account_pda),
+            ],
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.publishers[1].price, 10_000_000_000);
+        assert_eq!(updated.publishers[1].publish_slot, CURRENT_SLOT);
+        assert_eq!(updated.price, 10_000_000_000);
+        assert_eq!(updated.confidence, 10_000_000);
+        assert_eq!(updated.last_updated_slot, CURRENT_SLOT);
+        assert_eq!(updated.ema_price, 10_000_000_000);
+
+        let (price_history_pda, _) = price_history_address(&price_account_pda);
+        let price_history = PriceHistory::load(&result.get_account(&price_history_pda).unwrap().data).unwrap();
+        let latest = price_history.latest().unwrap();
+        assert_eq!(price_history.len, 1);
+        assert_eq!(latest.price, 10_000_000_000);
+        assert_eq!(latest.confidence, 10_000_000);
+        assert_eq!(latest.slot, CURRENT_SLOT);
+    }
+
+    #[test]
+    fn test_process_submit_price_below_quorum() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(2);
+
+        let result = mollusk.process_and_validate_instruction(
+            &submit_price_instruction(PUBLISHERS[0], price_account_pda, price_account_id, 10_000_000_000, CURRENT_SLOT),
+            &[
+                (PUBLISHERS[0], signer_account()),
+                (price_account_pda, price_account),
+                mock_price_history(&price_account_pda),
+            ],
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.publishers[0].price, 10_000_000_000);
+        assert_eq!(updated.price, 0);
+        assert_eq!(updated.last_updated_slot, 0);
+
+        let (price_history_pda, _) = price_history_address(&price_account_pda);
+        let price_history = PriceHistory::load(&result.get_account(&price_history_pda).unwrap().data).unwrap();
+        assert_eq!(price_history.len, 0);
+    }
+
+    #[test]
+    fn test_process_submit_price_median() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(2);
+
+        let result = mollusk.process_and_validate_instruction_chain(
+            &[
+                (&submit_price_instruction(PUBLISHERS[0], price_account_pda, price_account_id, 10_000_000_000, CURRENT_SLOT), &[Check::success()]),
+                (&submit_price_instruction(PUBLISHERS[1], price_account_pda, price_account_id, 12_000_000_000, CURRENT_SLOT), &[Check::success()]),
+                (&submit_price_instruction(PUBLISHERS[2], price_account_pda, price_account_id, 50_000_000_000, CURRENT_SLOT), &[Check::success()]),
+            ],
+            &[
+                (PUBLISHERS[0], signer_account()),
+                (PUBLISHERS[1], signer_account()),
+                (PUBLISHERS[2], signer_account()),
+                (price_account_pda, price_account),
+                mock_price_history(&price_account_pda),
+            ],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.price, 12_000_000_000);
+        assert_eq!(updated.confidence, 12_000_000);
+
+        let (price_history_pda, _) = price_history_address(&price_account_pda);
+        let price_history = PriceHistory::load(&result.get_account(&price_history_pda).unwrap().data).unwrap();
+        assert_eq!(price_history.len, 2);
+        assert_eq!(price_history.latest().unwrap().price, 12_000_000_000);
+    }
+
+    #[test]
+    fn test_process_submit_price_unknown_publisher() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(1);
+
+        mollusk.process_and_validate_instruction(
+            &submit_price_instruction(NOT_PUBLISHER, price_account_pda, price_account_id, 10_000_000_000, CURRENT_SLOT),
+            &[
+                (NOT_PUBLISHER, signer_account()),
+                (price_account_pda, price_account),
+                mock_price_history(&price_account_pda),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::UnknownPublisher as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_submit_price_future_publish_slot() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(1);
+
+        mollusk.process_and_validate_instruction(
+            &submit_price_instruction(PUBLISHERS[0], price_account_pda, price_account_id, 10_000_000_000, CURRENT_SLOT + 1),
+            &[
+                (PUBLISHERS[0], signer_account()),
+                (price_account_pda, price_account),
+                mock_price_history(&price_account_pda),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidPublishSlot as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_submit_price_deviation_too_large_keeps_submission() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(1);
+
+        let price_account = program_account(price_account_data(&PriceAccount {
+            status: PriceStatus::Trading as u8,
+            price: 10_000_000_000,
+            last_updated_slot: CURRENT_SLOT - 1,
+            max_deviation_bps: 1_000,
+            deviation_window_slots: 150,
+            ..PriceAccount::load(&price_account.data).unwrap()
+        }));
+
+        let result = mollusk.process_and_validate_instruction(
+            &submit_price_instruction(PUBLISHERS[0], price_account_pda, price_account_id, 20_000_000_000, CURRENT_SLOT),
+            &[
+                (PUBLISHERS[0], signer_account()),
+                (price_account_pda, price_account),
+                mock_price_history(&price_account_pda),
+            ],
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.publishers[0].price, 20_000_000_000);
+        assert_eq!(updated.publishers[0].publish_slot, CURRENT_SLOT);
+        assert_eq!(updated.price, 10_000_000_000);
+        assert_eq!(updated.last_updated_slot, CURRENT_SLOT - 1);
+
+        let (price_history_pda, _) = price_history_address(&price_account_pda);
+        let price_history = PriceHistory::load(&result.get_account(&price_history_pda).unwrap().data).unwrap();
+        assert_eq!(price_history.len, 0);
+    }
+
+    #[test]
+    fn test_process_submit_price_compute_units() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup(1);
+
+        let result = mollusk.process_and_validate_instruction(
+            &submit_price_instruction(PUBLISHERS[0], price_account_pda, price_account_id, 10_000_000_000, CURRENT_SLOT),
+            &[
+                (PUBLISHERS[0], signer_account()),
+                (price_account_pda, price_account),
+                mock_price_history(&price_account_pda),
+            ],
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
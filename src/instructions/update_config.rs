@@ -0,0 +1,188 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use pinocchio_log::log;
+
+use crate::{errors::PriceError, states::{Config, ZeroCopy}};
+
+/// Instruction data is `permissionless_creation: u8`, `default_max_staleness_secs: u64`,
+/// `default_max_deviation_bps: u16` and `default_deviation_window_slots: u64`. Replaces the
+/// creation policy and feed defaults at once; the admin is changed with `ProposeConfigAdmin`.
+/// New defaults only apply to feeds created afterwards.
+pub fn process_update_config(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 19 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [admin, config] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let permissionless_creation = instruction_data[0];
+
+    let default_max_staleness_secs = u64::from_le_bytes(
+        instruction_data[1..9].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let default_max_deviation_bps = u16::from_le_bytes(
+        instruction_data[9..11].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let default_deviation_window_slots = u64::from_le_bytes(
+        instruction_data[11..19].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    if permissionless_creation > 1 {
+        return Err(PriceError::InvalidConfigFlag.into());
+    };
+
+    if !admin.is_signer() {
+        return Err(PriceError::MissingRequiredSignature.into());
+    };
+
+    let mut config_mut = Config::from_account_info_mut(config)?;
+    config_mut.verify_address(config.key())?;
+
+    if config_mut.admin != *admin.key() {
+        return Err(PriceError::InvalidConfigAdmin.into());
+    };
+
+    config_mut.permissionless_creation = permissionless_creation;
+    config_mut.default_max_staleness_secs = default_max_staleness_secs;
+    config_mut.default_max_deviation_bps = default_max_deviation_bps;
+    config_mut.default_deviation_window_slots = default_deviation_window_slots;
+
+    log!("Config updated, permissionless creation: {}", permissionless_creation);
+
+    Ok(())
+}
+
+// =================== TESTING process_update_config ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{Config, ZeroCopy},
+        test_utils::{mock_config, mollusk, signer_account, PROGRAM_ID},
+    };
+
+    const ADMIN: Pubkey = Pubkey::new_from_array([1u8; 32]);
+    const NOT_ADMIN: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn update_config_instruction(signer: Pubkey, config_pda: Pubkey) -> Instruction {
+        update_config_instruction_with_flag(signer, config_pda, 1)
+    }
+
+    fn update_config_instruction_with_flag(signer: Pubkey, config_pda: Pubkey, permissionless_creation: u8) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(16u8);
+        instruction_data.push(permissionless_creation);
+        instruction_data.extend_from_slice(&120u64.to_le_bytes());
+        instruction_data.extend_from_slice(&250u16.to_le_bytes());
+        instruction_data.extend_from_slice(&20u64.to_le_bytes());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(config_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn setup() -> (Mollusk, Pubkey, Account) {
+        let (config_pda, config_account) = mock_config(ADMIN, 0);
+
+        (mollusk(), config_pda, config_account)
+    }
+
+    #[test]
+    fn test_process_update_config() {
+        let (mollusk, config_pda, config_account) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &update_config_instruction(ADMIN, config_pda),
+            &[
+                (ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+            &[Check::success()],
+        );
+
+        let updated = Config::load(&result.get_account(&config_pda).unwrap().data).unwrap();
+        assert_eq!(updated.admin, ADMIN.to_bytes());
+        assert_eq!(updated.permissionless_creation, 1);
+        assert_eq!(updated.default_max_staleness_secs, 120);
+        assert_eq!(updated.default_max_deviation_bps, 250);
+        assert_eq!(updated.default_deviation_window_slots, 20);
+    }
+
+    #[test]
+    fn test_process_update_config_not_admin() {
+        let (mollusk, config_pda, config_account) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &update_config_instruction(NOT_ADMIN, config_pda),
+            &[
+                (NOT_ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidConfigAdmin as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_update_config_invalid_flag() {
+        let (mollusk, config_pda, config_account) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &update_config_instruction_with_flag(ADMIN, config_pda, 2),
+            &[
+                (ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidConfigFlag as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_update_config_wrong_pda() {
+        let (mollusk, _config_pda, config_account) = setup();
+        let wrong_pda = Pubkey::new_unique();
+
+        mollusk.process_and_validate_instruction(
+            &update_config_instruction(ADMIN, wrong_pda),
+            &[
+                (ADMIN, signer_account()),
+                (wrong_pda, config_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidConfigAddress as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_update_config_compute_units() {
+        let (mollusk, config_pda, config_account) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &update_config_instruction(ADMIN, config_pda),
+            &[
+                (ADMIN, signer_account()),
+                (config_pda, config_account),
+            ],
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
@@ -0,0 +1,198 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use pinocchio_log::log;
+
+use super::price_update::load_for_authority;
+use crate::errors::PriceError;
+
+/// Instruction data is `price_account_id: u64`, `symbol: [u8; 16]`, `base_asset: [u8; 8]`,
+/// `quote_asset: [u8; 8]` and `decimals: u8`. Replaces all metadata fields at once.
+pub fn process_update_metadata(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+
+    if instruction_data.len() < 41 {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let [authority, price_account] = accounts else {
+        return Err(PriceError::IncorrectNumberOfAccounts.into());
+    };
+
+    let price_account_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let symbol: [u8; 16] = instruction_data[8..24].try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+    let base_asset: [u8; 8] = instruction_data[24..32].try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+    let quote_asset: [u8; 8] = instruction_data[32..40].try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+    let decimals = instruction_data[40];
+
+    let mut price_account_mut = load_for_authority(authority, price_account, price_account_id)?;
+
+    price_account_mut.set_metadata(symbol, base_asset, quote_asset, decimals)?;
+
+    log!("Price account {} metadata updated", price_account_id);
+
+    Ok(())
+}
+
+// =================== TESTING process_update_metadata ===================
+
+#[cfg(test)]
+mod tests {
+    use mollusk_svm::{Mollusk, result::Check};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    use crate::{
+        errors::PriceError,
+        states::{PriceAccount, PriceAccountSeeds, ZeroCopy},
+        test_utils::{mock_price_account, mollusk, price_account_address, price_account_data, price_account_symbol_address, program_account, signer_account, AUTHORITY, PRICE_ACCOUNT_ID, PROGRAM_ID},
+    };
+
+    const NOT_AUTHORITY: Pubkey = Pubkey::new_from_array([2u8; 32]);
+    const SYMBOL: [u8; 16] = *b"BTC/USDC\0\0\0\0\0\0\0\0";
+    const MAX_COMPUTE_UNITS: u64 = 3_000;
+
+    fn mock_price_account_data(bump: u8) -> Vec<u8> {
+        price_account_data(&PriceAccount {
+            symbol: *b"SOL/USD\0\0\0\0\0\0\0\0\0",
+            base_asset: *b"SOL\0\0\0\0\0",
+            quote_asset: *b"USD\0\0\0\0\0",
+            decimals: 9,
+            ..mock_price_account(bump)
+        })
+    }
+
+    fn update_metadata_instruction(signer: Pubkey, price_account_pda: Pubkey, price_account_id: u64, symbol: [u8; 16]) -> Instruction {
+        let mut instruction_data = vec![];
+        instruction_data.push(14u8);
+        instruction_data.extend_from_slice(&price_account_id.to_le_bytes());
+        instruction_data.extend_from_slice(&symbol);
+        instruction_data.extend_from_slice(b"BTC\0\0\0\0\0");
+        instruction_data.extend_from_slice(b"USDC\0\0\0\0");
+        instruction_data.push(8u8);
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(price_account_pda, false),
+            ],
+            data: instruction_data,
+        }
+    }
+
+    fn setup() -> (Mollusk, u64, Pubkey, Account) {
+        let mollusk = mollusk();
+
+        let price_account_id = PRICE_ACCOUNT_ID;
+
+        let (price_account_pda, bump) = price_account_address(price_account_id);
+
+        let price_account = program_account(mock_price_account_data(bump));
+
+        (mollusk, price_account_id, price_account_pda, price_account)
+    }
+
+    #[test]
+    fn test_process_update_metadata() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &update_metadata_instruction(AUTHORITY, price_account_pda, price_account_id, SYMBOL),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        let updated = PriceAccount::load(&result.get_account(&price_account_pda).unwrap().data).unwrap();
+        assert_eq!(updated.symbol, SYMBOL);
+        assert_eq!(updated.base_asset, *b"BTC\0\0\0\0\0");
+        assert_eq!(updated.quote_asset, *b"USDC\0\0\0\0");
+        assert_eq!(updated.decimals, 8);
+    }
+
+    #[test]
+    fn test_process_update_metadata_invalid_symbol() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &update_metadata_instruction(AUTHORITY, price_account_pda, price_account_id, *b"BTC\0USDC\0\0\0\0\0\0\0\0"),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidMetadata as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_update_metadata_not_authority() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        mollusk.process_and_validate_instruction(
+            &update_metadata_instruction(NOT_AUTHORITY, price_account_pda, price_account_id, SYMBOL),
+            &[
+                (NOT_AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::err(ProgramError::Custom(PriceError::InvalidAuthority as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_update_metadata_symbol_seeded() {
+        let mollusk = mollusk();
+
+        let (price_account_pda, bump) = price_account_symbol_address(b"SOL/USD");
+
+        let mut data = mock_price_account_data(bump);
+        let mut price_account = PriceAccount::load(&data).unwrap();
+        price_account.seed_kind = PriceAccountSeeds::Symbol as u8;
+        price_account.store(&mut data).unwrap();
+
+        let accounts = [
+            (AUTHORITY, signer_account()),
+            (price_account_pda, Account {
+                lamports: 1_000_000,
+                data,
+                owner: PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            }),
+        ];
+
+        mollusk.process_and_validate_instruction(
+            &update_metadata_instruction(AUTHORITY, price_account_pda, 0, *b"SOL/USD\0\0\0\0\0\0\0\0\0"),
+            &accounts,
+            &[Check::success()],
+        );
+
+        mollusk.process_and_validate_instruction(
+            &update_metadata_instruction(AUTHORITY, price_account_pda, 0, SYMBOL),
+            &accounts,
+            &[Check::err(ProgramError::Custom(PriceError::SymbolImmutable as u32))],
+        );
+    }
+
+    #[test]
+    fn test_process_update_metadata_compute_units() {
+        let (mollusk, price_account_id, price_account_pda, price_account) = setup();
+
+        let result = mollusk.process_and_validate_instruction(
+            &update_metadata_instruction(AUTHORITY, price_account_pda, price_account_id, SYMBOL),
+            &[
+                (AUTHORITY, signer_account()),
+                (price_account_pda, price_account),
+            ],
+            &[Check::success()],
+        );
+
+        assert!(result.compute_units_consumed <= MAX_COMPUTE_UNITS, "consumed {} CUs", result.compute_units_consumed);
+    }
+}
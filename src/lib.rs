@@ -1,17 +1,24 @@
-use pinocchio::{account_info::AccountInfo, pubkey::Pubkey, ProgramResult, program_error::ProgramError,*};
+use pinocchio::{account_info::AccountInfo, pubkey::Pubkey, ProgramResult, program_error::ProgramError};
 use pinocchio_pubkey::*;
 
 use crate::instructions::PriceInstructions;
 
 declare_id!("4zSrGy87rYtohmWK7PLBsojskZQa38GMwmoQkeK1nJSD");
 
+pub mod consumer;
+pub mod errors;
 pub mod instructions;
+pub mod seeds;
 pub mod states;
 
-entrypoint!(process_instruction);
+#[cfg(test)]
+pub(crate) mod test_utils;
 
-fn process_instruction(
-    program_id: &Pubkey,
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8]
 ) -> ProgramResult {
@@ -22,7 +29,22 @@ fn process_instruction(
         PriceInstructions::CreatePriceAccount => instructions::create_price_account::process_create_price_account(accounts, instruction_data)?,
         PriceInstructions::SetPrice => instructions::set_price::process_set_price(accounts, instruction_data)?,
         PriceInstructions::ModifyPrice => instructions::modify_price::process_modify_price(accounts, instruction_data)?,
-        PriceInstructions::GetPrice => {},
+        PriceInstructions::GetPrice => instructions::get_price::process_get_price(accounts, instruction_data)?,
+        PriceInstructions::ProposeAuthority => instructions::propose_authority::process_propose_authority(accounts, instruction_data)?,
+        PriceInstructions::AcceptAuthority => instructions::accept_authority::process_accept_authority(accounts, instruction_data)?,
+        PriceInstructions::SubmitPrice => instructions::submit_price::process_submit_price(accounts, instruction_data)?,
+        PriceInstructions::AddPublisher => instructions::add_publisher::process_add_publisher(accounts, instruction_data)?,
+        PriceInstructions::RemovePublisher => instructions::remove_publisher::process_remove_publisher(accounts, instruction_data)?,
+        PriceInstructions::GetTwap => instructions::get_twap::process_get_twap(accounts, instruction_data)?,
+        PriceInstructions::OverrideCircuitBreaker => instructions::override_circuit_breaker::process_override_circuit_breaker(accounts, instruction_data)?,
+        PriceInstructions::SetStatus => instructions::set_status::process_set_status(accounts, instruction_data)?,
+        PriceInstructions::ClosePriceAccount => instructions::close_price_account::process_close_price_account(accounts, instruction_data)?,
+        PriceInstructions::MigratePriceAccount => instructions::migrate_price_account::process_migrate_price_account(accounts, instruction_data)?,
+        PriceInstructions::UpdateMetadata => instructions::update_metadata::process_update_metadata(accounts, instruction_data)?,
+        PriceInstructions::InitializeConfig => instructions::initialize_config::process_initialize_config(accounts, instruction_data)?,
+        PriceInstructions::UpdateConfig => instructions::update_config::process_update_config(accounts, instruction_data)?,
+        PriceInstructions::ProposeConfigAdmin => instructions::propose_config_admin::process_propose_config_admin(accounts, instruction_data)?,
+        PriceInstructions::AcceptConfigAdmin => instructions::accept_config_admin::process_accept_config_admin(accounts, instruction_data)?,
     }
 
     Ok(())
@@ -0,0 +1,90 @@
+use pinocchio::pubkey::Pubkey;
+use pinocchio_pubkey::pubkey;
+
+/// Seed prefix of id-derived feeds: `[PRICE_ACCOUNT_SEED, price_account_id.to_le_bytes()]`.
+pub const PRICE_ACCOUNT_SEED: &[u8] = b"price_feed_account";
+/// Seed prefix of symbol-derived feeds: `[PRICE_ACCOUNT_SYMBOL_SEED, symbol_seed(&symbol)]`.
+pub const PRICE_ACCOUNT_SYMBOL_SEED: &[u8] = b"price_feed";
+/// Seed prefix of a feed's history: `[PRICE_HISTORY_SEED, price_account]`.
+pub const PRICE_HISTORY_SEED: &[u8] = b"price_history";
+/// Seed of the program-wide config singleton: `[CONFIG_SEED]`.
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Upgradeable BPF loader, which owns this program's `ProgramData` account.
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// Strips the zero padding from a stored symbol, e.g. `b"SOL/USD\0\0..."` becomes `b"SOL/USD"`.
+pub fn symbol_seed(symbol: &[u8]) -> &[u8] {
+    let len = symbol.iter().position(|byte| *byte == 0).unwrap_or(symbol.len());
+    &symbol[..len]
+}
+
+// The seed builders below are pure, so the program and off-chain clients derive the same
+// addresses from them, e.g. `Pubkey::find_program_address(&price_account_seeds(&id.to_le_bytes()), &program_id)`.
+
+pub fn price_account_seeds(price_account_id: &[u8; 8]) -> [&[u8]; 2] {
+    [PRICE_ACCOUNT_SEED, price_account_id]
+}
+
+pub fn price_account_symbol_seeds(symbol: &[u8]) -> [&[u8]; 2] {
+    [PRICE_ACCOUNT_SYMBOL_SEED, symbol_seed(symbol)]
+}
+
+pub fn price_history_seeds(price_account: &[u8]) -> [&[u8]; 2] {
+    [PRICE_HISTORY_SEED, price_account]
+}
+
+pub fn config_seeds() -> [&'static [u8]; 1] {
+    [CONFIG_SEED]
+}
+
+/// Seeds of the program's `ProgramData` account under `BPF_LOADER_UPGRADEABLE_ID`.
+pub fn program_data_seeds() -> [&'static [u8]; 1] {
+    [&crate::ID]
+}
+
+// `pinocchio::pubkey::find_program_address` is only implemented on-chain, so these are only
+// available to programs; clients derive addresses from the seed builders above.
+
+#[cfg(target_os = "solana")]
+pub fn find_price_account_address(price_account_id: u64) -> (Pubkey, u8) {
+    pinocchio::pubkey::find_program_address(&price_account_seeds(&price_account_id.to_le_bytes()), &crate::ID)
+}
+
+#[cfg(target_os = "solana")]
+pub fn find_price_account_symbol_address(symbol: &[u8]) -> (Pubkey, u8) {
+    pinocchio::pubkey::find_program_address(&price_account_symbol_seeds(symbol), &crate::ID)
+}
+
+#[cfg(target_os = "solana")]
+pub fn find_price_history_address(price_account: &Pubkey) -> (Pubkey, u8) {
+    pinocchio::pubkey::find_program_address(&price_history_seeds(price_account), &crate::ID)
+}
+
+#[cfg(target_os = "solana")]
+pub fn find_config_address() -> (Pubkey, u8) {
+    pinocchio::pubkey::find_program_address(&config_seeds(), &crate::ID)
+}
+
+#[cfg(target_os = "solana")]
+pub fn find_program_data_address() -> (Pubkey, u8) {
+    pinocchio::pubkey::find_program_address(&program_data_seeds(), &BPF_LOADER_UPGRADEABLE_ID)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{price_account_seeds, price_account_symbol_seeds, symbol_seed, PRICE_ACCOUNT_SEED, PRICE_ACCOUNT_SYMBOL_SEED};
+
+    #[test]
+    fn test_symbol_seed() {
+        assert_eq!(symbol_seed(b"SOL/USD\0\0\0\0\0\0\0\0\0"), b"SOL/USD");
+        assert_eq!(symbol_seed(b"BTC/USDC"), b"BTC/USDC");
+        assert_eq!(symbol_seed(&[0u8; 16]), b"");
+    }
+
+    #[test]
+    fn test_price_account_seeds() {
+        assert_eq!(price_account_seeds(&13u64.to_le_bytes()), [PRICE_ACCOUNT_SEED, &13u64.to_le_bytes()[..]]);
+        assert_eq!(price_account_symbol_seeds(b"SOL/USD\0\0\0\0\0\0\0\0\0"), [PRICE_ACCOUNT_SYMBOL_SEED, b"SOL/USD"]);
+    }
+}
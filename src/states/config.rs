@@ -0,0 +1,158 @@
+use pinocchio::{account_info::{AccountInfo, RefMut}, program_error::ProgramError, pubkey::{self, Pubkey}};
+use shank::ShankAccount;
+
+use super::ZeroCopy;
+use crate::{errors::PriceError, seeds::config_seeds};
+
+/// Program-wide settings, stored in the singleton PDA `["config"]`. Same `repr(C)`
+/// conventions as `PriceAccount`.
+///
+/// | offset | size | field                            |
+/// |--------|------|----------------------------------|
+/// | 0      | 8    | `discriminator`                  |
+/// | 8      | 1    | `version`                        |
+/// | 9      | 1    | `bump`                           |
+/// | 10     | 1    | `permissionless_creation`        |
+/// | 11     | 5    | padding                          |
+/// | 16     | 32   | `admin`                          |
+/// | 48     | 8    | `default_max_staleness_secs`     |
+/// | 56     | 8    | `default_deviation_window_slots` |
+/// | 64     | 2    | `default_max_deviation_bps`      |
+/// | 66     | 6    | padding                          |
+/// | 72     | 32   | `pending_admin`                  |
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, ShankAccount)]
+pub struct Config {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub bump: u8,
+    /// When 0, only `admin` may create feeds. Migrating legacy feeds is always admin-only.
+    pub permissionless_creation: u8,
+    pub _padding0: [u8; 5],
+    pub admin: Pubkey,
+    /// Inherited by new feeds created with `max_staleness_secs` `u64::MAX`.
+    pub default_max_staleness_secs: u64,
+    /// Inherited by new feeds created with `deviation_window_slots` `u64::MAX`.
+    pub default_deviation_window_slots: u64,
+    /// Inherited by new feeds created with `max_deviation_bps` `u16::MAX`.
+    pub default_max_deviation_bps: u16,
+    pub _padding1: [u8; 6],
+    /// Admin proposed by `ProposeConfigAdmin`, or the zero key when no transfer is pending.
+    pub pending_admin: Pubkey,
+}
+
+const _: () = {
+    use core::mem::{align_of, offset_of, size_of};
+
+    assert!(size_of::<Config>() == 104);
+    assert!(align_of::<Config>() == 8);
+    assert!(offset_of!(Config, permissionless_creation) == 10);
+    assert!(offset_of!(Config, admin) == 16);
+    assert!(offset_of!(Config, default_max_staleness_secs) == 48);
+    assert!(offset_of!(Config, default_deviation_window_slots) == 56);
+    assert!(offset_of!(Config, default_max_deviation_bps) == 64);
+    assert!(offset_of!(Config, pending_admin) == 72);
+};
+
+impl Config {
+    /// Fails with `InvalidConfigAdmin` if feed creation is admin-only and `creator` is not the admin.
+    pub fn check_creator(&self, creator: &Pubkey) -> Result<(), ProgramError> {
+        if self.permissionless_creation == 0 && self.admin != *creator {
+            return Err(PriceError::InvalidConfigAdmin.into());
+        };
+
+        Ok(())
+    }
+
+    /// Replaces each feed parameter set to its type's maximum with the configured default.
+    /// Zero is passed through, so a feed can still disable its circuit breaker explicitly.
+    pub fn feed_defaults(&self, max_staleness_secs: u64, max_deviation_bps: u16, deviation_window_slots: u64) -> (u64, u16, u64) {
+        (
+            if max_staleness_secs == u64::MAX { self.default_max_staleness_secs } else { max_staleness_secs },
+            if max_deviation_bps == u16::MAX { self.default_max_deviation_bps } else { max_deviation_bps },
+            if deviation_window_slots == u64::MAX { self.default_deviation_window_slots } else { deviation_window_slots },
+        )
+    }
+
+    /// Checks that `key` is the config PDA, using the stored bump.
+    pub fn verify_address(&self, key: &Pubkey) -> Result<(), ProgramError> {
+        let [prefix] = config_seeds();
+        let config_pda = pubkey::create_program_address(&[prefix, &[self.bump]], &crate::ID)
+            .map_err(|_| PriceError::InvalidConfigAddress)?;
+
+        if *key != config_pda {
+            return Err(PriceError::InvalidConfigAddress.into());
+        };
+
+        Ok(())
+    }
+
+    /// Writes the account header into a freshly allocated config account.
+    pub fn init(accounts: &AccountInfo, bump: u8) -> Result<RefMut<'_, Self>, ProgramError> {
+        let mut config = Self::init_header(accounts)?;
+        config.bump = bump;
+
+        Ok(config)
+    }
+}
+
+// SAFETY: `Config` is `repr(C)`, 8-byte aligned, starts with the discriminator and version,
+// and holds only integer and byte-array fields.
+unsafe impl ZeroCopy for Config {
+    const DISCRIMINATOR: [u8; 8] = *b"PRCONFIG";
+    const VERSION: u8 = 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use pinocchio::program_error::ProgramError;
+
+    use super::{Config, ZeroCopy};
+    use crate::errors::PriceError;
+
+    const ADMIN: [u8; 32] = [1u8; 32];
+    const OTHER: [u8; 32] = [2u8; 32];
+
+    #[test]
+    fn test_check_creator() {
+        let mut config = Config { admin: ADMIN, ..Default::default() };
+
+        assert!(config.check_creator(&ADMIN).is_ok());
+        assert_eq!(config.check_creator(&OTHER), Err(ProgramError::Custom(PriceError::InvalidConfigAdmin as u32)));
+
+        config.permissionless_creation = 1;
+        assert!(config.check_creator(&OTHER).is_ok());
+    }
+
+    #[test]
+    fn test_feed_defaults() {
+        let config = Config {
+            default_max_staleness_secs: 60,
+            default_max_deviation_bps: 500,
+            default_deviation_window_slots: 10,
+            ..Default::default()
+        };
+
+        assert_eq!(config.feed_defaults(u64::MAX, u16::MAX, u64::MAX), (60, 500, 10));
+        assert_eq!(config.feed_defaults(30, 1_000, u64::MAX), (30, 1_000, 10));
+        assert_eq!(config.feed_defaults(0, 0, 0), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_load_store_unaligned() {
+        let config = Config {
+            discriminator: Config::DISCRIMINATOR,
+            version: Config::VERSION,
+            bump: 254,
+            admin: ADMIN,
+            default_max_staleness_secs: 60,
+            ..Default::default()
+        };
+
+        let mut buffer = [0u8; Config::SIZE + 1];
+        config.store(&mut buffer[1..]).unwrap();
+
+        assert_eq!(Config::load(&buffer[1..]).unwrap(), config);
+        assert!(Config::load(&buffer[1..Config::SIZE]).is_err());
+    }
+}
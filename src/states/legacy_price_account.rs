@@ -0,0 +1,83 @@
+use pinocchio::program_error::ProgramError;
+
+use crate::errors::PriceError;
+
+/// Layout of feeds created before versioning was introduced: a bare `repr(Rust)` struct with no
+/// discriminator, an `f64` price and no authority. Only read by `MigratePriceAccount`.
+///
+/// | offset | size | field                    |
+/// |--------|------|--------------------------|
+/// | 0      | 8    | `price` (f64)            |
+/// | 8      | 8    | `last_updated_timestamp` |
+/// | 16     | 1    | `price_account_bump`     |
+/// | 17     | 7    | padding                  |
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LegacyPriceAccountV0 {
+    pub price: f64,
+    pub last_updated_timestamp: i64,
+    pub price_account_bump: u8,
+}
+
+impl LegacyPriceAccountV0 {
+    pub const SIZE: usize = 24;
+
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < Self::SIZE {
+            return Err(PriceError::InvalidAccountSize.into());
+        };
+
+        Ok(LegacyPriceAccountV0 {
+            price: f64::from_le_bytes(data[0..8].try_into().map_err(|_| PriceError::InvalidAccountSize)?),
+            last_updated_timestamp: i64::from_le_bytes(data[8..16].try_into().map_err(|_| PriceError::InvalidAccountSize)?),
+            price_account_bump: data[16],
+        })
+    }
+
+    /// Converts the floating-point price to a fixed-point integer at `exponent`, rounding to
+    /// the nearest unit. Fails with `InvalidPrice` for NaN, infinities and out-of-range values.
+    pub fn price_at_exponent(&self, exponent: i32) -> Result<i64, ProgramError> {
+        let scaled = self.price * 10f64.powi(-exponent);
+
+        if !scaled.is_finite() || scaled.abs() >= i64::MAX as f64 {
+            return Err(PriceError::InvalidPrice.into());
+        };
+
+        Ok(scaled.round() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pinocchio::program_error::ProgramError;
+
+    use super::LegacyPriceAccountV0;
+    use crate::errors::PriceError;
+
+    #[test]
+    fn test_load() {
+        let mut data = [0u8; LegacyPriceAccountV0::SIZE];
+        data[0..8].copy_from_slice(&123.45f64.to_le_bytes());
+        data[8..16].copy_from_slice(&1_700_000_000i64.to_le_bytes());
+        data[16] = 254;
+
+        assert_eq!(LegacyPriceAccountV0::load(&data), Ok(LegacyPriceAccountV0 {
+            price: 123.45,
+            last_updated_timestamp: 1_700_000_000,
+            price_account_bump: 254,
+        }));
+        assert!(LegacyPriceAccountV0::load(&data[..LegacyPriceAccountV0::SIZE - 1]).is_err());
+    }
+
+    #[test]
+    fn test_price_at_exponent() {
+        let legacy = |price| LegacyPriceAccountV0 { price, last_updated_timestamp: 0, price_account_bump: 0 };
+        let invalid = Err(ProgramError::Custom(PriceError::InvalidPrice as u32));
+
+        assert_eq!(legacy(123.45).price_at_exponent(-8), Ok(12_345_000_000));
+        assert_eq!(legacy(123.45).price_at_exponent(-1), Ok(1_235));
+        assert_eq!(legacy(-0.5).price_at_exponent(0), Ok(-1));
+        assert_eq!(legacy(f64::NAN).price_at_exponent(-8), invalid);
+        assert_eq!(legacy(f64::INFINITY).price_at_exponent(-8), invalid);
+        assert_eq!(legacy(1e30).price_at_exponent(-8), invalid);
+    }
+}
@@ -1,2 +1,10 @@
+pub mod config;
+pub mod legacy_price_account;
 pub mod price_account;
-pub use price_account::*;
\ No newline at end of file
+pub mod price_history;
+pub mod zero_copy;
+pub use config::*;
+pub use legacy_price_account::*;
+pub use price_account::*;
+pub use price_history::*;
+pub use zero_copy::*;
@@ -1,34 +1,920 @@
-use pinocchio::{account_info::{AccountInfo, Ref, RefMut}, program_error::ProgramError, *};
-use shank::ShankAccount;
+use pinocchio::{program_error::ProgramError, pubkey::{self, Pubkey}};
+use shank::{ShankAccount, ShankType};
 
-#[derive(Debug, Clone, Copy, PartialEq, ShankAccount)]
+use super::ZeroCopy;
+use crate::{errors::PriceError, seeds::{price_account_seeds, price_account_symbol_seeds}};
+
+/// On-chain layout of a price feed. The layout is `repr(C)` with explicit padding so byte
+/// offsets are stable for off-chain decoders; all integers are little-endian.
+///
+/// | offset | size | field                      |
+/// |--------|------|----------------------------|
+/// | 0      | 8    | `discriminator`            |
+/// | 8      | 1    | `version`                  |
+/// | 9      | 1    | `status`                   |
+/// | 10     | 1    | `price_account_bump`       |
+/// | 11     | 1    | `deviation_override`       |
+/// | 12     | 1    | `allow_negative`           |
+/// | 13     | 1    | `seed_kind`                |
+/// | 14     | 1    | `price_history_bump`       |
+/// | 15     | 1    | padding                    |
+/// | 16     | 8    | `price`                    |
+/// | 24     | 8    | `confidence`               |
+/// | 32     | 4    | `exponent`                 |
+/// | 36     | 2    | `max_confidence_ratio_bps` |
+/// | 38     | 2    | `max_deviation_bps`        |
+/// | 40     | 8    | `last_updated_timestamp`   |
+/// | 48     | 32   | `authority`                |
+/// | 80     | 32   | `pending_authority`        |
+/// | 112    | 8    | `max_staleness_secs`       |
+/// | 120    | 8    | `last_updated_slot`        |
+/// | 128    | 1    | `num_publishers`           |
+/// | 129    | 1    | `min_publishers`           |
+/// | 130    | 6    | padding                    |
+/// | 136    | 448  | `publishers`               |
+/// | 584    | 8    | `ema_price`                |
+/// | 592    | 8    | `ema_confidence`           |
+/// | 600    | 8    | `ema_half_life_slots`      |
+/// | 608    | 8    | `deviation_window_slots`   |
+/// | 616    | 8    | `min_price`                |
+/// | 624    | 8    | `max_price`                |
+/// | 632    | 16   | `symbol`                   |
+/// | 648    | 8    | `base_asset`               |
+/// | 656    | 8    | `quote_asset`              |
+/// | 664    | 1    | `decimals`                 |
+/// | 665    | 7    | padding                    |
+/// | 672    | 8    | `last_publish_slot`        |
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, ShankAccount)]
 pub struct PriceAccount {
-    pub price: f64,
-    pub last_updated_timestamp: i64,
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    #[idl_type("PriceStatus")]
+    pub status: u8,
     pub price_account_bump: u8,
+    /// Non-zero when the authority has armed a one-shot bypass of the deviation check.
+    pub deviation_override: u8,
+    /// Non-zero when zero and negative prices are accepted (e.g. spreads or rates).
+    pub allow_negative: u8,
+    /// How the feed address is derived; see `PriceAccountSeeds`.
+    #[idl_type("PriceAccountSeeds")]
+    pub seed_kind: u8,
+    /// Bump of the feed's `["price_history", price_account]` PDA.
+    pub price_history_bump: u8,
+    pub _padding0: [u8; 1],
+    /// Price as a fixed-point integer: the real value is `price * 10^exponent`.
+    pub price: i64,
+    /// Uncertainty of `price`, expressed at the same exponent.
+    pub confidence: u64,
+    pub exponent: i32,
+    /// Largest accepted `confidence / |price|`, in basis points.
+    pub max_confidence_ratio_bps: u16,
+    /// Largest accepted move from the previous price, in basis points; 0 disables the check.
+    pub max_deviation_bps: u16,
+    pub last_updated_timestamp: i64,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    /// Oldest price, in seconds, that readers accept.
+    pub max_staleness_secs: u64,
+    pub last_updated_slot: u64,
+    /// Number of occupied entries at the front of `publishers`.
+    pub num_publishers: u8,
+    /// Fresh publisher submissions required before the aggregate is recomputed.
+    pub min_publishers: u8,
+    pub _padding2: [u8; 6],
+    pub publishers: [PublisherSlot; 8],
+    /// Exponential moving average of `price`, weighted by slots elapsed between updates.
+    pub ema_price: i64,
+    pub ema_confidence: u64,
+    /// Slots after which an update's weight in the EMA has halved; 0 disables smoothing.
+    pub ema_half_life_slots: u64,
+    /// Updates further apart than this many slots are not subject to `max_deviation_bps`.
+    pub deviation_window_slots: u64,
+    /// Lowest accepted price at the feed exponent; `i64::MIN` leaves prices unbounded below.
+    pub min_price: i64,
+    /// Highest accepted price at the feed exponent; `i64::MAX` leaves prices unbounded above.
+    pub max_price: i64,
+    /// Human-readable pair name such as `SOL/USD`, ASCII and zero-padded.
+    pub symbol: [u8; 16],
+    /// Identifier of the priced asset, ASCII and zero-padded.
+    pub base_asset: [u8; 8],
+    /// Identifier of the asset the price is quoted in, ASCII and zero-padded.
+    pub quote_asset: [u8; 8],
+    /// Decimals of the base asset, for clients converting between token amounts and prices.
+    pub decimals: u8,
+    pub _padding3: [u8; 7],
+    /// Publisher-reported slot of the latest `SetPrice` or `ModifyPrice`; later updates
+    /// reporting an older slot are rejected.
+    pub last_publish_slot: u64,
+}
+
+/// Latest submission of one authorized publisher, stored at the feed exponent.
+///
+/// | offset | size | field          |
+/// |--------|------|----------------|
+/// | 0      | 32   | `publisher`    |
+/// | 32     | 8    | `price`        |
+/// | 40     | 8    | `confidence`   |
+/// | 48     | 8    | `publish_slot` |
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, ShankType)]
+pub struct PublisherSlot {
+    pub publisher: Pubkey,
+    pub price: i64,
+    pub confidence: u64,
+    /// Slot the submission was published at; 0 if the publisher has not submitted yet.
+    pub publish_slot: u64,
+}
+
+const _: () = {
+    use core::mem::{align_of, offset_of, size_of};
+
+    assert!(size_of::<PriceAccount>() == 680);
+    assert!(align_of::<PriceAccount>() == 8);
+    assert!(offset_of!(PriceAccount, version) == 8);
+    assert!(offset_of!(PriceAccount, status) == 9);
+    assert!(offset_of!(PriceAccount, price_account_bump) == 10);
+    assert!(offset_of!(PriceAccount, deviation_override) == 11);
+    assert!(offset_of!(PriceAccount, allow_negative) == 12);
+    assert!(offset_of!(PriceAccount, seed_kind) == 13);
+    assert!(offset_of!(PriceAccount, price_history_bump) == 14);
+    assert!(offset_of!(PriceAccount, price) == 16);
+    assert!(offset_of!(PriceAccount, confidence) == 24);
+    assert!(offset_of!(PriceAccount, exponent) == 32);
+    assert!(offset_of!(PriceAccount, max_confidence_ratio_bps) == 36);
+    assert!(offset_of!(PriceAccount, max_deviation_bps) == 38);
+    assert!(offset_of!(PriceAccount, last_updated_timestamp) == 40);
+    assert!(offset_of!(PriceAccount, authority) == 48);
+    assert!(offset_of!(PriceAccount, pending_authority) == 80);
+    assert!(offset_of!(PriceAccount, max_staleness_secs) == 112);
+    assert!(offset_of!(PriceAccount, last_updated_slot) == 120);
+    assert!(offset_of!(PriceAccount, num_publishers) == 128);
+    assert!(offset_of!(PriceAccount, min_publishers) == 129);
+    assert!(offset_of!(PriceAccount, publishers) == 136);
+    assert!(offset_of!(PriceAccount, ema_price) == 584);
+    assert!(offset_of!(PriceAccount, ema_confidence) == 592);
+    assert!(offset_of!(PriceAccount, ema_half_life_slots) == 600);
+    assert!(offset_of!(PriceAccount, deviation_window_slots) == 608);
+    assert!(offset_of!(PriceAccount, min_price) == 616);
+    assert!(offset_of!(PriceAccount, max_price) == 624);
+    assert!(offset_of!(PriceAccount, symbol) == 632);
+    assert!(offset_of!(PriceAccount, base_asset) == 648);
+    assert!(offset_of!(PriceAccount, quote_asset) == 656);
+    assert!(offset_of!(PriceAccount, decimals) == 664);
+    assert!(offset_of!(PriceAccount, last_publish_slot) == 672);
+    assert!(size_of::<PublisherSlot>() == 56);
+    assert!(offset_of!(PublisherSlot, price) == 32);
+    assert!(offset_of!(PublisherSlot, confidence) == 40);
+    assert!(offset_of!(PublisherSlot, publish_slot) == 48);
+    assert!(PriceAccount::MAX_PUBLISHERS == 8);
+};
+
+impl PublisherSlot {
+    /// Whether the submission is recent enough at `slot` to count towards the aggregate.
+    pub fn is_fresh(&self, slot: u64) -> bool {
+        self.publish_slot != 0
+            && slot.saturating_sub(self.publish_slot) <= PriceAccount::MAX_PUBLISHER_AGE_SLOTS
+    }
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ShankType)]
+pub enum PriceStatus {
+    /// No price has been published since the account was created.
+    Unknown = 0,
+    Trading = 1,
+    /// Set by the authority; consumers must not use the price. Updates are still recorded.
+    Halted = 2,
+}
+
+impl TryFrom<u8> for PriceStatus {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PriceStatus::Unknown),
+            1 => Ok(PriceStatus::Trading),
+            2 => Ok(PriceStatus::Halted),
+            _ => Err(PriceError::InvalidStatus.into())
+        }
+    }
+}
+
+/// Seeds the feed address is derived from.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ShankType)]
+pub enum PriceAccountSeeds {
+    /// `[PRICE_ACCOUNT_SEED, price_account_id.to_le_bytes()]`
+    Id = 0,
+    /// `[PRICE_ACCOUNT_SYMBOL_SEED, symbol]`; the symbol can then no longer change.
+    Symbol = 1,
+}
+
+impl TryFrom<u8> for PriceAccountSeeds {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PriceAccountSeeds::Id),
+            1 => Ok(PriceAccountSeeds::Symbol),
+            _ => Err(PriceError::InvalidSeedKind.into())
+        }
+    }
+}
+
+/// How `ModifyPrice` interprets its amount relative to the current price.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ShankType)]
+pub enum PriceAdjustment {
+    /// Amount is added to the current price.
+    Delta = 0,
+    /// Amount is a signed change of the current price in basis points.
+    Bps = 1,
+}
+
+impl TryFrom<u8> for PriceAdjustment {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PriceAdjustment::Delta),
+            1 => Ok(PriceAdjustment::Bps),
+            _ => Err(PriceError::InvalidAdjustment.into())
+        }
+    }
 }
 
 impl PriceAccount {
-    pub const SIZE: usize = core::mem::size_of::<PriceAccount>();
+    /// Written by `ClosePriceAccount` so a closed feed can never be loaded again. The closed
+    /// account is shrunk to just this discriminator and kept rent exempt, so its address
+    /// cannot be created again either.
+    pub const CLOSED_DISCRIMINATOR: [u8; 8] = *b"CLOSEDPA";
+    pub const MIN_EXPONENT: i32 = -18;
+    pub const MAX_EXPONENT: i32 = 18;
+    pub const BPS_DENOMINATOR: u64 = 10_000;
+    pub const MAX_PUBLISHERS: usize = 8;
+    /// Oldest publisher submission, in slots, that still counts towards the aggregate.
+    pub const MAX_PUBLISHER_AGE_SLOTS: u64 = 25;
+    /// Fixed-point denominator of EMA weights.
+    pub const EMA_SCALE: u64 = 1 << 32;
 
-    pub fn from_account_info(accounts: &AccountInfo) -> Result<Ref<Self>, ProgramError> {
-        if accounts.data_len() < PriceAccount::SIZE {
-            return Err(ProgramError::InvalidAccountData);
+    /// Rescales `value` from `from_exponent` to `to_exponent`. Moving to a coarser exponent
+    /// truncates toward zero, which is how submitted prices are stored; moving to a finer one
+    /// fails with `ArithmeticOverflow` if the result does not fit in an `i64`.
+    pub fn scale_value(value: i64, from_exponent: i32, to_exponent: i32) -> Result<i64, ProgramError> {
+        let shift = from_exponent.checked_sub(to_exponent).ok_or(ProgramError::ArithmeticOverflow)?;
+        let factor = 10i64.checked_pow(shift.unsigned_abs()).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        if shift >= 0 {
+            value.checked_mul(factor).ok_or(ProgramError::ArithmeticOverflow)
+        } else {
+            Ok(value / factor)
+        }
+    }
+
+    /// Rescales a confidence interval like `scale_value`, except that moving to a coarser
+    /// exponent rounds up so the stored interval is never narrower than the submitted one.
+    pub fn scale_confidence(confidence: u64, from_exponent: i32, to_exponent: i32) -> Result<u64, ProgramError> {
+        let shift = from_exponent.checked_sub(to_exponent).ok_or(ProgramError::ArithmeticOverflow)?;
+        let factor = 10u64.checked_pow(shift.unsigned_abs()).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        if shift >= 0 {
+            confidence.checked_mul(factor).ok_or(ProgramError::ArithmeticOverflow)
+        } else {
+            Ok(confidence.div_ceil(factor))
+        }
+    }
+
+    /// Returns whether `confidence` is within the feed's `max_confidence_ratio_bps` of `price`.
+    pub fn confidence_within_limit(&self, price: i64, confidence: u64) -> bool {
+        (confidence as u128) * (Self::BPS_DENOMINATOR as u128)
+            <= (price.unsigned_abs() as u128) * (self.max_confidence_ratio_bps as u128)
+    }
+
+    /// Fails with `DeviationTooLarge` if `price` moves more than `max_deviation_bps` away from
+    /// the current price and the last update is within `deviation_window_slots` of `slot`.
+    /// A current price of 0, only possible with `allow_negative`, gives no reference to measure
+    /// a relative move against, so any price is accepted.
+    pub fn check_deviation(&self, price: i64, slot: u64) -> Result<(), ProgramError> {
+        if self.max_deviation_bps == 0
+            || self.status == PriceStatus::Unknown as u8
+            || self.price == 0
+            || slot.saturating_sub(self.last_updated_slot) > self.deviation_window_slots
+        {
+            return Ok(());
+        };
+
+        let deviation = (price as i128 - self.price as i128).unsigned_abs();
+
+        if deviation * Self::BPS_DENOMINATOR as u128
+            > self.price.unsigned_abs() as u128 * self.max_deviation_bps as u128
+        {
+            return Err(PriceError::DeviationTooLarge.into());
         };
 
-        Ok(Ref::map(accounts.try_borrow_data()?, |data| unsafe {
-            &*(data.as_ptr() as *const Self)
-        }))
+        Ok(())
     }
 
-    pub fn from_account_info_mut(accounts: &AccountInfo) -> Result<RefMut<Self>, ProgramError> {
-        if accounts.data_len() < PriceAccount::SIZE {
-            return Err(ProgramError::InvalidAccountData);
+    /// Applies `amount` to the current price. A `Delta` amount must already be at the feed
+    /// exponent. Fails with `NoBasePrice` before the first update and `ArithmeticOverflow` if
+    /// the result does not fit in an `i64`.
+    pub fn adjusted_price(&self, adjustment: PriceAdjustment, amount: i64) -> Result<i64, ProgramError> {
+        if self.status == PriceStatus::Unknown as u8 {
+            return Err(PriceError::NoBasePrice.into());
         };
 
-        Ok(RefMut::map(accounts.try_borrow_mut_data()?, |data| unsafe {
-            &mut *(data.as_mut_ptr() as *mut Self)
-        }))
+        match adjustment {
+            PriceAdjustment::Delta => self.price.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow),
+            PriceAdjustment::Bps => {
+                let change = (self.price as i128)
+                    .checked_mul(amount as i128)
+                    .ok_or(ProgramError::ArithmeticOverflow)?
+                    / Self::BPS_DENOMINATOR as i128;
+
+                i64::try_from(self.price as i128 + change).map_err(|_| ProgramError::ArithmeticOverflow)
+            }
+        }
     }
 
-}
\ No newline at end of file
+    /// Checks `price`, already at the feed exponent, against the feed's sign rule and its
+    /// `min_price` / `max_price` bounds.
+    pub fn validate_price(&self, price: i64) -> Result<(), ProgramError> {
+        if self.allow_negative == 0 {
+            if price < 0 {
+                return Err(PriceError::NegativePrice.into());
+            };
+
+            if price == 0 {
+                return Err(PriceError::ZeroPrice.into());
+            };
+        };
+
+        if price < self.min_price {
+            return Err(PriceError::PriceBelowMinimum.into());
+        };
+
+        if price > self.max_price {
+            return Err(PriceError::PriceAboveMaximum.into());
+        };
+
+        Ok(())
+    }
+
+    /// Fails with `InvalidMetadata` unless `symbol`, `base_asset` and `quote_asset` are
+    /// non-empty printable ASCII followed only by zero padding.
+    pub fn check_metadata(symbol: &[u8; 16], base_asset: &[u8; 8], quote_asset: &[u8; 8]) -> Result<(), ProgramError> {
+        if !is_valid_identifier(symbol) || !is_valid_identifier(base_asset) || !is_valid_identifier(quote_asset) {
+            return Err(PriceError::InvalidMetadata.into());
+        };
+
+        Ok(())
+    }
+
+    /// Replaces the feed metadata after `check_metadata`. Fails with `SymbolImmutable` if the
+    /// feed address is derived from its symbol and `symbol` differs from it.
+    pub fn set_metadata(&mut self, symbol: [u8; 16], base_asset: [u8; 8], quote_asset: [u8; 8], decimals: u8) -> Result<(), ProgramError> {
+        Self::check_metadata(&symbol, &base_asset, &quote_asset)?;
+
+        if self.seed_kind == PriceAccountSeeds::Symbol as u8 && symbol != self.symbol {
+            return Err(PriceError::SymbolImmutable.into());
+        };
+
+        self.symbol = symbol;
+        self.base_asset = base_asset;
+        self.quote_asset = quote_asset;
+        self.decimals = decimals;
+
+        Ok(())
+    }
+
+    /// Returns the stored price expressed at `target_exponent`.
+    pub fn price_at_exponent(&self, target_exponent: i32) -> Result<i64, ProgramError> {
+        Self::scale_value(self.price, self.exponent, target_exponent)
+    }
+
+    /// Fails with `StalePrice` if the price is older than `max_staleness_secs` at
+    /// `unix_timestamp`. `max_staleness_override` can only tighten the feed's bound.
+    pub fn check_staleness(&self, unix_timestamp: i64, max_staleness_override: Option<u64>) -> Result<(), ProgramError> {
+        let max_staleness_secs = match max_staleness_override {
+            Some(max_staleness_override) => self.max_staleness_secs.min(max_staleness_override),
+            None => self.max_staleness_secs,
+        };
+
+        let age = unix_timestamp.saturating_sub(self.last_updated_timestamp).max(0) as u64;
+
+        if age > max_staleness_secs {
+            return Err(PriceError::StalePrice.into());
+        };
+
+        Ok(())
+    }
+
+    /// Fails with `StalePrice` if more than `max_staleness_slots` slots have passed since the
+    /// last update at `slot`.
+    pub fn check_slot_staleness(&self, slot: u64, max_staleness_slots: u64) -> Result<(), ProgramError> {
+        if slot.saturating_sub(self.last_updated_slot) > max_staleness_slots {
+            return Err(PriceError::StalePrice.into());
+        };
+
+        Ok(())
+    }
+
+    /// Weight, out of `EMA_SCALE`, the previous EMA keeps after `elapsed_slots`: roughly
+    /// `2^(-elapsed_slots / half_life_slots)`, interpolated linearly within a half-life.
+    pub fn ema_decay(elapsed_slots: u64, half_life_slots: u64) -> u64 {
+        if half_life_slots == 0 {
+            return 0;
+        };
+
+        let halvings = elapsed_slots / half_life_slots;
+
+        if halvings >= 64 {
+            return 0;
+        };
+
+        let remainder = (elapsed_slots % half_life_slots) as u128;
+        let within_half_life = Self::EMA_SCALE as u128
+            - (Self::EMA_SCALE as u128 / 2) * remainder / half_life_slots as u128;
+
+        (within_half_life >> halvings) as u64
+    }
+
+    /// Folds a new `price`/`confidence` observed at `slot` into the EMA. Must run before
+    /// `last_updated_slot` and `status` are updated; the first published price seeds the EMA.
+    pub fn update_ema(&mut self, price: i64, confidence: u64, slot: u64) {
+        if self.status == PriceStatus::Unknown as u8 {
+            self.ema_price = price;
+            self.ema_confidence = confidence;
+            return;
+        };
+
+        let decay = Self::ema_decay(slot.saturating_sub(self.last_updated_slot), self.ema_half_life_slots) as i128;
+        let scale = Self::EMA_SCALE as i128;
+
+        // Both results are convex combinations of values that fit their types.
+        self.ema_price = ((self.ema_price as i128 * decay + price as i128 * (scale - decay)) / scale) as i64;
+        self.ema_confidence = ((self.ema_confidence as i128 * decay + confidence as i128 * (scale - decay)) / scale) as u64;
+    }
+
+    /// Stores a validated update. The feed starts trading on its first update, but a halted
+    /// feed stays halted until the authority changes its status.
+    pub fn record_update(&mut self, price: i64, confidence: u64, unix_timestamp: i64, slot: u64) {
+        self.update_ema(price, confidence, slot);
+        self.price = price;
+        self.confidence = confidence;
+        self.last_updated_timestamp = unix_timestamp;
+        self.last_updated_slot = slot;
+
+        if self.status != PriceStatus::Halted as u8 {
+            self.status = PriceStatus::Trading as u8;
+        };
+    }
+
+    /// The occupied publisher slots.
+    pub fn publishers(&self) -> &[PublisherSlot] {
+        &self.publishers[..(self.num_publishers as usize).min(Self::MAX_PUBLISHERS)]
+    }
+
+    pub fn publisher_index(&self, publisher: &Pubkey) -> Option<usize> {
+        self.publishers().iter().position(|slot| slot.publisher == *publisher)
+    }
+
+    /// Median price and confidence of the publishers that are fresh at `slot`, or `None` if
+    /// fewer than `min_publishers` (and at least one) are fresh. With an even count the two
+    /// middle values are averaged, rounding toward negative infinity.
+    pub fn aggregate(&self, slot: u64) -> Option<(i64, u64)> {
+        let mut prices = [0i128; Self::MAX_PUBLISHERS];
+        let mut confidences = [0i128; Self::MAX_PUBLISHERS];
+        let mut fresh = 0;
+
+        for publisher in self.publishers() {
+            if publisher.is_fresh(slot) {
+                prices[fresh] = publisher.price as i128;
+                confidences[fresh] = publisher.confidence as i128;
+                fresh += 1;
+            }
+        }
+
+        if fresh == 0 || fresh < self.min_publishers as usize {
+            return None;
+        };
+
+        Some((median(&mut prices[..fresh]) as i64, median(&mut confidences[..fresh]) as u64))
+    }
+
+    pub fn status(&self) -> Result<PriceStatus, ProgramError> {
+        PriceStatus::try_from(self.status)
+    }
+
+    /// Moves the feed between `Trading` and `Halted`. Fails with `InvalidStatusTransition` when
+    /// asked for `Unknown` and with `PriceNotPublished` before the first price is recorded.
+    pub fn set_status(&mut self, status: PriceStatus) -> Result<(), ProgramError> {
+        if status == PriceStatus::Unknown {
+            return Err(PriceError::InvalidStatusTransition.into());
+        };
+
+        if self.status == PriceStatus::Unknown as u8 {
+            return Err(PriceError::PriceNotPublished.into());
+        };
+
+        self.status = status as u8;
+
+        Ok(())
+    }
+
+    /// Checks that `key` is the feed PDA for `price_account_id`. Re-derives the address with the
+    /// stored bump via `create_program_address`, which is much cheaper than the bump search
+    /// done by `find_program_address`. Symbol-derived feeds are checked against their stored
+    /// symbol, so `price_account_id` is ignored for them.
+    pub fn verify_address(&self, key: &Pubkey, price_account_id: u64) -> Result<(), ProgramError> {
+        let price_account_id = price_account_id.to_le_bytes();
+        let [prefix, seed] = match PriceAccountSeeds::try_from(self.seed_kind)? {
+            PriceAccountSeeds::Id => price_account_seeds(&price_account_id),
+            PriceAccountSeeds::Symbol => price_account_symbol_seeds(&self.symbol),
+        };
+
+        let price_account_pda = pubkey::create_program_address(
+            &[prefix, seed, &[self.price_account_bump]],
+            &crate::ID
+        ).map_err(|_| PriceError::InvalidPriceAccountAddress)?;
+
+        if *key != price_account_pda {
+            return Err(PriceError::InvalidPriceAccountAddress.into());
+        };
+
+        Ok(())
+    }
+}
+
+// SAFETY: `PriceAccount` is `repr(C)`, 8-byte aligned, starts with the discriminator and
+// version, and holds only integer and byte-array fields.
+unsafe impl ZeroCopy for PriceAccount {
+    const DISCRIMINATOR: [u8; 8] = *b"PRICEACC";
+    const VERSION: u8 = 1;
+}
+
+fn is_valid_identifier(value: &[u8]) -> bool {
+    let len = value.iter().position(|byte| *byte == 0).unwrap_or(value.len());
+
+    len > 0
+        && value[..len].iter().all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+        && value[len..].iter().all(|byte| *byte == 0)
+}
+
+fn median(values: &mut [i128]) -> i128 {
+    values.sort_unstable();
+    let middle = values.len() / 2;
+
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]).div_euclid(2)
+    } else {
+        values[middle]
+    }
+}
+
+// =================== TESTING PriceAccount ===================
+
+#[cfg(test)]
+mod tests {
+    use pinocchio::program_error::ProgramError;
+
+    use super::{PriceAccount, PriceAccountSeeds, PriceAdjustment, PriceStatus, PublisherSlot, ZeroCopy};
+    use crate::errors::PriceError;
+
+    #[test]
+    fn test_load_store_unaligned() {
+        let price_account = PriceAccount {
+            discriminator: PriceAccount::DISCRIMINATOR,
+            version: PriceAccount::VERSION,
+            price: -42,
+            confidence: 7,
+            exponent: -8,
+            last_updated_timestamp: 1_700_000_000,
+            authority: [9u8; 32],
+            ..Default::default()
+        };
+
+        let mut buffer = [0u8; PriceAccount::SIZE + 1];
+        price_account.store(&mut buffer[1..]).unwrap();
+
+        assert_eq!(&buffer[1..9], b"PRICEACC");
+        assert_eq!(buffer[1 + 8], PriceAccount::VERSION);
+        assert_eq!(&buffer[1 + 16..1 + 24], &(-42i64).to_le_bytes());
+        assert_eq!(&buffer[1 + 32..1 + 36], &(-8i32).to_le_bytes());
+        assert_eq!(&buffer[1 + 48..1 + 80], &[9u8; 32]);
+        assert_eq!(PriceAccount::load(&buffer[1..]).unwrap(), price_account);
+        assert!(PriceAccount::load(&buffer[1..PriceAccount::SIZE]).is_err());
+    }
+
+    #[test]
+    fn test_scale_value() {
+        assert_eq!(PriceAccount::scale_value(12_345, -2, -2), Ok(12_345));
+        assert_eq!(PriceAccount::scale_value(12_345, -2, -5), Ok(12_345_000));
+        assert_eq!(PriceAccount::scale_value(12_345, -2, 0), Ok(123));
+        assert_eq!(PriceAccount::scale_value(-12_345, -2, 0), Ok(-123));
+        assert_eq!(PriceAccount::scale_value(1, 0, -40), Err(ProgramError::ArithmeticOverflow));
+        assert_eq!(PriceAccount::scale_value(i64::MAX, -8, -9), Err(ProgramError::ArithmeticOverflow));
+    }
+
+    #[test]
+    fn test_scale_confidence() {
+        assert_eq!(PriceAccount::scale_confidence(12_345, -2, -2), Ok(12_345));
+        assert_eq!(PriceAccount::scale_confidence(12_345, -2, -5), Ok(12_345_000));
+        assert_eq!(PriceAccount::scale_confidence(12_345, -2, 0), Ok(124));
+        assert_eq!(PriceAccount::scale_confidence(12_300, -2, 0), Ok(123));
+        assert_eq!(PriceAccount::scale_confidence(1, -8, 0), Ok(1));
+        assert_eq!(PriceAccount::scale_confidence(0, -8, 0), Ok(0));
+        assert_eq!(PriceAccount::scale_confidence(u64::MAX, -2, -2), Ok(u64::MAX));
+        assert_eq!(PriceAccount::scale_confidence(u64::MAX, -8, -9), Err(ProgramError::ArithmeticOverflow));
+    }
+
+    #[test]
+    fn test_price_at_exponent() {
+        let price_account = PriceAccount {
+            price: 14_250_000_000,
+            exponent: -8,
+            ..Default::default()
+        };
+
+        assert_eq!(price_account.price_at_exponent(-2), Ok(14_250));
+        assert_eq!(price_account.price_at_exponent(-10), Ok(1_425_000_000_000));
+    }
+
+    #[test]
+    fn test_check_staleness() {
+        let price_account = PriceAccount {
+            last_updated_timestamp: 1_000,
+            max_staleness_secs: 60,
+            ..Default::default()
+        };
+
+        assert_eq!(price_account.check_staleness(1_060, None), Ok(()));
+        assert_eq!(price_account.check_staleness(1_061, None), Err(ProgramError::Custom(PriceError::StalePrice as u32)));
+        assert_eq!(price_account.check_staleness(900, None), Ok(()));
+        assert_eq!(price_account.check_staleness(1_030, Some(30)), Ok(()));
+        assert_eq!(price_account.check_staleness(1_031, Some(30)), Err(ProgramError::Custom(PriceError::StalePrice as u32)));
+        assert_eq!(price_account.check_staleness(1_061, Some(120)), Err(ProgramError::Custom(PriceError::StalePrice as u32)));
+    }
+
+    #[test]
+    fn test_check_slot_staleness() {
+        let price_account = PriceAccount {
+            last_updated_slot: 500,
+            ..Default::default()
+        };
+
+        assert_eq!(price_account.check_slot_staleness(525, 25), Ok(()));
+        assert_eq!(price_account.check_slot_staleness(526, 25), Err(ProgramError::Custom(PriceError::StalePrice as u32)));
+        assert_eq!(price_account.check_slot_staleness(400, 25), Ok(()));
+    }
+
+    #[test]
+    fn test_confidence_within_limit() {
+        let price_account = PriceAccount {
+            max_confidence_ratio_bps: 100,
+            ..Default::default()
+        };
+
+        assert!(price_account.confidence_within_limit(10_000, 100));
+        assert!(price_account.confidence_within_limit(-10_000, 100));
+        assert!(!price_account.confidence_within_limit(10_000, 101));
+        assert!(!price_account.confidence_within_limit(0, 1));
+        assert!(price_account.confidence_within_limit(i64::MAX, u64::MAX / 10_000));
+    }
+
+    fn publisher(seed: u8, price: i64, confidence: u64, publish_slot: u64) -> PublisherSlot {
+        PublisherSlot {
+            publisher: [seed; 32],
+            price,
+            confidence,
+            publish_slot,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_median() {
+        let mut price_account = PriceAccount {
+            num_publishers: 3,
+            min_publishers: 1,
+            ..Default::default()
+        };
+        price_account.publishers[0] = publisher(1, 100, 3, 1_000);
+        price_account.publishers[1] = publisher(2, 300, 1, 1_000);
+        price_account.publishers[2] = publisher(3, 200, 2, 1_000);
+
+        assert_eq!(price_account.aggregate(1_000), Some((200, 2)));
+
+        price_account.num_publishers = 2;
+        assert_eq!(price_account.aggregate(1_000), Some((200, 2)));
+
+        price_account.publishers[1] = publisher(2, -301, 1, 1_000);
+        assert_eq!(price_account.aggregate(1_000), Some((-101, 2)));
+    }
+
+    #[test]
+    fn test_aggregate_quorum_and_freshness() {
+        let mut price_account = PriceAccount {
+            num_publishers: 3,
+            min_publishers: 2,
+            ..Default::default()
+        };
+        price_account.publishers[0] = publisher(1, 100, 1, 1_000);
+        price_account.publishers[1] = publisher(2, 300, 1, 1_000 - PriceAccount::MAX_PUBLISHER_AGE_SLOTS - 1);
+        price_account.publishers[2] = publisher(3, 200, 1, 0);
+
+        assert_eq!(price_account.aggregate(1_000), None);
+
+        price_account.publishers[1].publish_slot = 1_000 - PriceAccount::MAX_PUBLISHER_AGE_SLOTS;
+        assert_eq!(price_account.aggregate(1_000), Some((200, 1)));
+
+        price_account.min_publishers = 0;
+        price_account.num_publishers = 0;
+        assert_eq!(price_account.aggregate(1_000), None);
+    }
+
+    #[test]
+    fn test_publisher_index() {
+        let mut price_account = PriceAccount {
+            num_publishers: 1,
+            ..Default::default()
+        };
+        price_account.publishers[0] = publisher(1, 0, 0, 0);
+        price_account.publishers[1] = publisher(2, 0, 0, 0);
+
+        assert_eq!(price_account.publisher_index(&[1u8; 32]), Some(0));
+        assert_eq!(price_account.publisher_index(&[2u8; 32]), None);
+    }
+
+    #[test]
+    fn test_ema_decay() {
+        let scale = PriceAccount::EMA_SCALE;
+
+        assert_eq!(PriceAccount::ema_decay(0, 10), scale);
+        assert_eq!(PriceAccount::ema_decay(5, 10), scale * 3 / 4);
+        assert_eq!(PriceAccount::ema_decay(10, 10), scale / 2);
+        assert_eq!(PriceAccount::ema_decay(20, 10), scale / 4);
+        assert_eq!(PriceAccount::ema_decay(640, 10), 0);
+        assert_eq!(PriceAccount::ema_decay(1, 0), 0);
+    }
+
+    #[test]
+    fn test_update_ema() {
+        let mut price_account = PriceAccount {
+            ema_half_life_slots: 10,
+            ..Default::default()
+        };
+
+        price_account.update_ema(1_000, 10, 100);
+        assert_eq!((price_account.ema_price, price_account.ema_confidence), (1_000, 10));
+
+        price_account.status = PriceStatus::Trading as u8;
+        price_account.last_updated_slot = 100;
+        price_account.update_ema(2_000, 30, 110);
+        assert_eq!((price_account.ema_price, price_account.ema_confidence), (1_500, 20));
+
+        price_account.last_updated_slot = 110;
+        price_account.update_ema(-2_000, 20, 110);
+        assert_eq!((price_account.ema_price, price_account.ema_confidence), (1_500, 20));
+
+        price_account.ema_half_life_slots = 0;
+        price_account.update_ema(-2_000, 20, 111);
+        assert_eq!((price_account.ema_price, price_account.ema_confidence), (-2_000, 20));
+    }
+
+    #[test]
+    fn test_check_deviation() {
+        let mut price_account = PriceAccount {
+            price: 10_000,
+            status: PriceStatus::Trading as u8,
+            last_updated_slot: 100,
+            max_deviation_bps: 500,
+            deviation_window_slots: 10,
+            ..Default::default()
+        };
+
+        let too_large = Err(ProgramError::Custom(PriceError::DeviationTooLarge as u32));
+
+        assert_eq!(price_account.check_deviation(10_500, 110), Ok(()));
+        assert_eq!(price_account.check_deviation(9_500, 110), Ok(()));
+        assert_eq!(price_account.check_deviation(10_501, 110), too_large);
+        assert_eq!(price_account.check_deviation(-10_000, 110), too_large);
+        assert_eq!(price_account.check_deviation(20_000, 111), Ok(()));
+
+        price_account.status = PriceStatus::Unknown as u8;
+        assert_eq!(price_account.check_deviation(20_000, 110), Ok(()));
+
+        price_account.status = PriceStatus::Trading as u8;
+        price_account.price = 0;
+        assert_eq!(price_account.check_deviation(-20_000, 110), Ok(()));
+
+        price_account.price = 10_000;
+        price_account.max_deviation_bps = 0;
+        assert_eq!(price_account.check_deviation(20_000, 110), Ok(()));
+    }
+
+    #[test]
+    fn test_adjusted_price() {
+        let mut price_account = PriceAccount {
+            price: 10_000,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            price_account.adjusted_price(PriceAdjustment::Delta, 1),
+            Err(ProgramError::Custom(PriceError::NoBasePrice as u32))
+        );
+
+        price_account.status = PriceStatus::Trading as u8;
+        assert_eq!(price_account.adjusted_price(PriceAdjustment::Delta, 500), Ok(10_500));
+        assert_eq!(price_account.adjusted_price(PriceAdjustment::Delta, -10_500), Ok(-500));
+        assert_eq!(price_account.adjusted_price(PriceAdjustment::Bps, 250), Ok(10_250));
+        assert_eq!(price_account.adjusted_price(PriceAdjustment::Bps, -10_000), Ok(0));
+        assert_eq!(price_account.adjusted_price(PriceAdjustment::Delta, i64::MAX), Err(ProgramError::ArithmeticOverflow));
+        assert_eq!(price_account.adjusted_price(PriceAdjustment::Bps, i64::MAX), Err(ProgramError::ArithmeticOverflow));
+
+        assert_eq!(PriceAdjustment::try_from(2), Err(ProgramError::Custom(PriceError::InvalidAdjustment as u32)));
+    }
+
+    #[test]
+    fn test_set_metadata() {
+        let mut price_account = PriceAccount::default();
+        let invalid = Err(ProgramError::Custom(PriceError::InvalidMetadata as u32));
+
+        let mut symbol = [0u8; 16];
+        symbol[..7].copy_from_slice(b"SOL/USD");
+
+        assert_eq!(price_account.set_metadata(symbol, *b"SOL\0\0\0\0\0", *b"USD\0\0\0\0\0", 9), Ok(()));
+        assert_eq!(&price_account.symbol[..7], b"SOL/USD");
+        assert_eq!(price_account.decimals, 9);
+
+        assert_eq!(price_account.set_metadata([0u8; 16], *b"SOL\0\0\0\0\0", *b"USD\0\0\0\0\0", 9), invalid);
+        assert_eq!(price_account.set_metadata(symbol, *b"SOL\0\0\0\0X", *b"USD\0\0\0\0\0", 9), invalid);
+        assert_eq!(price_account.set_metadata(symbol, *b"SOL\0\0\0\0\0", *b"US\nD\0\0\0\0", 9), invalid);
+        assert_eq!(&price_account.symbol[..7], b"SOL/USD");
+
+        price_account.seed_kind = PriceAccountSeeds::Symbol as u8;
+        assert_eq!(price_account.set_metadata(symbol, *b"SOL\0\0\0\0\0", *b"USDC\0\0\0\0", 9), Ok(()));
+        assert_eq!(
+            price_account.set_metadata(*b"SOL/USDC\0\0\0\0\0\0\0\0", *b"SOL\0\0\0\0\0", *b"USDC\0\0\0\0", 9),
+            Err(ProgramError::Custom(PriceError::SymbolImmutable as u32))
+        );
+    }
+
+    #[test]
+    fn test_validate_price() {
+        let mut price_account = PriceAccount { min_price: i64::MIN, max_price: i64::MAX, ..Default::default() };
+
+        assert_eq!(price_account.validate_price(1), Ok(()));
+        assert_eq!(price_account.validate_price(0), Err(ProgramError::Custom(PriceError::ZeroPrice as u32)));
+        assert_eq!(price_account.validate_price(-1), Err(ProgramError::Custom(PriceError::NegativePrice as u32)));
+
+        price_account.allow_negative = 1;
+        assert_eq!(price_account.validate_price(0), Ok(()));
+        assert_eq!(price_account.validate_price(-1), Ok(()));
+
+        price_account.min_price = -100;
+        price_account.max_price = 100;
+        assert_eq!(price_account.validate_price(-100), Ok(()));
+        assert_eq!(price_account.validate_price(100), Ok(()));
+        assert_eq!(price_account.validate_price(-101), Err(ProgramError::Custom(PriceError::PriceBelowMinimum as u32)));
+        assert_eq!(price_account.validate_price(101), Err(ProgramError::Custom(PriceError::PriceAboveMaximum as u32)));
+
+        price_account.min_price = 0;
+        price_account.max_price = 0;
+        assert_eq!(price_account.validate_price(0), Ok(()));
+        assert_eq!(price_account.validate_price(1), Err(ProgramError::Custom(PriceError::PriceAboveMaximum as u32)));
+    }
+
+    #[test]
+    fn test_record_update_keeps_halted() {
+        let mut price_account = PriceAccount::default();
+
+        price_account.record_update(1_000, 10, 1_700_000_000, 100);
+        assert_eq!(price_account.status(), Ok(PriceStatus::Trading));
+        assert_eq!(price_account.ema_price, 1_000);
+
+        price_account.status = PriceStatus::Halted as u8;
+        price_account.record_update(1_100, 10, 1_700_000_001, 101);
+        assert_eq!(price_account.status(), Ok(PriceStatus::Halted));
+        assert_eq!((price_account.price, price_account.last_updated_slot), (1_100, 101));
+
+        assert_eq!(PriceStatus::try_from(3), Err(ProgramError::Custom(PriceError::InvalidStatus as u32)));
+    }
+
+    #[test]
+    fn test_set_status() {
+        let mut price_account = PriceAccount::default();
+
+        assert_eq!(price_account.set_status(PriceStatus::Trading), Err(ProgramError::Custom(PriceError::PriceNotPublished as u32)));
+        assert_eq!(price_account.set_status(PriceStatus::Halted), Err(ProgramError::Custom(PriceError::PriceNotPublished as u32)));
+
+        price_account.record_update(1_000, 10, 1_700_000_000, 100);
+        assert!(price_account.set_status(PriceStatus::Halted).is_ok());
+        assert_eq!(price_account.status(), Ok(PriceStatus::Halted));
+        assert!(price_account.set_status(PriceStatus::Trading).is_ok());
+        assert_eq!(price_account.status(), Ok(PriceStatus::Trading));
+
+        assert_eq!(price_account.set_status(PriceStatus::Unknown), Err(ProgramError::Custom(PriceError::InvalidStatusTransition as u32)));
+        assert_eq!(price_account.status(), Ok(PriceStatus::Trading));
+    }
+}
@@ -0,0 +1,267 @@
+use pinocchio::{account_info::{AccountInfo, RefMut}, program_error::ProgramError, pubkey::Pubkey};
+use shank::{ShankAccount, ShankType};
+
+use super::ZeroCopy;
+use crate::errors::PriceError;
+
+/// Per-feed ring buffer of recent updates, stored in the PDA
+/// `["price_history", price_account]`. Same `repr(C)` conventions as `PriceAccount`.
+///
+/// | offset | size | field           |
+/// |--------|------|-----------------|
+/// | 0      | 8    | `discriminator` |
+/// | 8      | 1    | `version`       |
+/// | 9      | 1    | `bump`          |
+/// | 10     | 6    | padding         |
+/// | 16     | 32   | `price_account` |
+/// | 48     | 8    | `next_index`    |
+/// | 56     | 8    | `len`           |
+/// | 64     | 2048 | `entries`       |
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, ShankAccount)]
+pub struct PriceHistory {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub bump: u8,
+    pub _padding0: [u8; 6],
+    /// The feed this history belongs to.
+    pub price_account: Pubkey,
+    /// Slot in `entries` the next update is written to.
+    pub next_index: u64,
+    /// Number of valid entries, saturating at `CAPACITY`.
+    pub len: u64,
+    pub entries: [PriceHistoryEntry; 64],
+}
+
+/// One recorded update, at the feed exponent.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, ShankType)]
+pub struct PriceHistoryEntry {
+    pub price: i64,
+    pub confidence: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+}
+
+const _: () = {
+    use core::mem::{align_of, offset_of, size_of};
+
+    assert!(size_of::<PriceHistory>() == 2112);
+    assert!(align_of::<PriceHistory>() == 8);
+    assert!(offset_of!(PriceHistory, bump) == 9);
+    assert!(offset_of!(PriceHistory, price_account) == 16);
+    assert!(offset_of!(PriceHistory, next_index) == 48);
+    assert!(offset_of!(PriceHistory, len) == 56);
+    assert!(offset_of!(PriceHistory, entries) == 64);
+    assert!(size_of::<PriceHistoryEntry>() == 32);
+    assert!(PriceHistory::CAPACITY == 64);
+};
+
+impl Default for PriceHistory {
+    fn default() -> Self {
+        PriceHistory {
+            discriminator: [0u8; 8],
+            version: 0,
+            bump: 0,
+            _padding0: [0u8; 6],
+            price_account: Pubkey::default(),
+            next_index: 0,
+            len: 0,
+            entries: [PriceHistoryEntry::default(); 64],
+        }
+    }
+}
+
+impl PriceHistory {
+    pub const CLOSED_DISCRIMINATOR: [u8; 8] = *b"CLOSEDPH";
+    pub const CAPACITY: usize = 64;
+
+    /// Records `entry`, overwriting the oldest one once the buffer is full.
+    pub fn append(&mut self, entry: PriceHistoryEntry) {
+        let index = self.next_index as usize % Self::CAPACITY;
+
+        self.entries[index] = entry;
+        self.next_index = ((index + 1) % Self::CAPACITY) as u64;
+        self.len = (self.len + 1).min(Self::CAPACITY as u64);
+    }
+
+    /// Recorded entries, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &PriceHistoryEntry> {
+        let len = (self.len as usize).min(Self::CAPACITY);
+        let start = (self.next_index as usize + Self::CAPACITY - len) % Self::CAPACITY;
+
+        (0..len).map(move |offset| &self.entries[(start + offset) % Self::CAPACITY])
+    }
+
+    /// The most recent entry, if any.
+    pub fn latest(&self) -> Option<&PriceHistoryEntry> {
+        self.iter().last()
+    }
+
+    /// Time-weighted average price and confidence over the `window_secs` seconds ending at
+    /// `now`. Each entry is held until the next one, and the latest until `now`. Fails with
+    /// `InvalidTwapWindow` for an empty window and with `InsufficientHistory` unless the oldest
+    /// retained entry is at or before the window start.
+    pub fn twap(&self, now: i64, window_secs: u64) -> Result<(i64, u64), ProgramError> {
+        let window = i64::try_from(window_secs).map_err(|_| PriceError::InsufficientHistory)?;
+
+        if window == 0 {
+            return Err(PriceError::InvalidTwapWindow.into());
+        };
+
+        let start = now.checked_sub(window).ok_or(PriceError::InsufficientHistory)?;
+
+        match self.iter().next() {
+            Some(oldest) if oldest.timestamp <= start => {},
+            _ => return Err(PriceError::InsufficientHistory.into()),
+        };
+
+        // |price| * total weight is at most i64::MAX^2, so the sums cannot overflow an i128.
+        let mut price_sum = 0i128;
+        let mut confidence_sum = 0i128;
+        let mut entries = self.iter().peekable();
+
+        while let Some(entry) = entries.next() {
+            let end = entries.peek().map_or(now, |next| next.timestamp).min(now);
+            let begin = entry.timestamp.max(start);
+
+            if end > begin {
+                let weight = (end - begin) as i128;
+                price_sum += entry.price as i128 * weight;
+                confidence_sum += entry.confidence as i128 * weight;
+            };
+        }
+
+        Ok(((price_sum / window as i128) as i64, (confidence_sum / window as i128) as u64))
+    }
+
+    /// Checks that this history belongs to `price_account` and carries the bump recorded on
+    /// the feed. Only this program writes history accounts, and it derives their address from
+    /// `price_account` at creation, so no PDA derivation is needed.
+    pub fn verify_feed(&self, price_account: &Pubkey, bump: u8) -> Result<(), ProgramError> {
+        if self.price_account != *price_account || self.bump != bump {
+            return Err(PriceError::InvalidPriceHistoryAddress.into());
+        };
+
+        Ok(())
+    }
+
+    /// Writes the account header into a freshly allocated history account.
+    pub fn init<'a>(accounts: &'a AccountInfo, price_account: &Pubkey, bump: u8) -> Result<RefMut<'a, Self>, ProgramError> {
+        let mut price_history = Self::init_header(accounts)?;
+        price_history.bump = bump;
+        price_history.price_account = *price_account;
+
+        Ok(price_history)
+    }
+}
+
+// SAFETY: `PriceHistory` is `repr(C)`, 8-byte aligned, starts with the discriminator and
+// version, and holds only integer and byte-array fields.
+unsafe impl ZeroCopy for PriceHistory {
+    const DISCRIMINATOR: [u8; 8] = *b"PRICEHST";
+    const VERSION: u8 = 1;
+}
+
+// =================== TESTING PriceHistory ===================
+
+#[cfg(test)]
+mod tests {
+    use pinocchio::program_error::ProgramError;
+
+    use super::{PriceHistory, PriceHistoryEntry, ZeroCopy};
+    use crate::errors::PriceError;
+
+    fn entry(slot: u64) -> PriceHistoryEntry {
+        PriceHistoryEntry {
+            price: slot as i64 * 10,
+            confidence: 1,
+            timestamp: slot as i64,
+            slot,
+        }
+    }
+
+    #[test]
+    fn test_append_before_wrap() {
+        let mut price_history = PriceHistory::default();
+        assert_eq!(price_history.iter().count(), 0);
+        assert_eq!(price_history.latest(), None);
+
+        for slot in 1..=3 {
+            price_history.append(entry(slot));
+        }
+
+        let slots: Vec<u64> = price_history.iter().map(|entry| entry.slot).collect();
+        assert_eq!(slots, [1, 2, 3]);
+        assert_eq!(price_history.latest(), Some(&entry(3)));
+    }
+
+    #[test]
+    fn test_append_wraps_chronologically() {
+        let mut price_history = PriceHistory::default();
+        let total = PriceHistory::CAPACITY as u64 + 5;
+
+        for slot in 1..=total {
+            price_history.append(entry(slot));
+        }
+
+        let slots: Vec<u64> = price_history.iter().map(|entry| entry.slot).collect();
+        let expected: Vec<u64> = (6..=total).collect();
+        assert_eq!(slots, expected);
+        assert_eq!(price_history.len, PriceHistory::CAPACITY as u64);
+        assert_eq!(price_history.latest(), Some(&entry(total)));
+    }
+
+    #[test]
+    fn test_load_store_unaligned() {
+        let mut price_history = PriceHistory {
+            discriminator: PriceHistory::DISCRIMINATOR,
+            version: PriceHistory::VERSION,
+            price_account: [7u8; 32],
+            ..Default::default()
+        };
+        price_history.append(entry(9));
+
+        let mut buffer = vec![0u8; PriceHistory::SIZE + 1];
+        price_history.store(&mut buffer[1..]).unwrap();
+
+        assert_eq!(&buffer[1..9], b"PRICEHST");
+        assert_eq!(&buffer[1 + 16..1 + 48], &[7u8; 32]);
+        assert_eq!(PriceHistory::load(&buffer[1..]).unwrap(), price_history);
+    }
+
+    #[test]
+    fn test_twap() {
+        let mut price_history = PriceHistory::default();
+        price_history.append(PriceHistoryEntry { price: 100, confidence: 10, timestamp: 1_000, slot: 1 });
+        price_history.append(PriceHistoryEntry { price: 200, confidence: 20, timestamp: 1_030, slot: 2 });
+        price_history.append(PriceHistoryEntry { price: 400, confidence: 40, timestamp: 1_050, slot: 3 });
+
+        // 100 for 30s, 200 for 20s, 400 for 10s.
+        assert_eq!(price_history.twap(1_060, 60), Ok((183, 18)));
+        // Window starting mid-entry: 100 for 10s, 200 for 20s, 400 for 10s.
+        assert_eq!(price_history.twap(1_060, 40), Ok((225, 22)));
+        // Window entirely after the last update.
+        assert_eq!(price_history.twap(1_100, 10), Ok((400, 40)));
+    }
+
+    #[test]
+    fn test_twap_insufficient_history() {
+        let mut price_history = PriceHistory::default();
+        assert_eq!(price_history.twap(1_000, 10), Err(ProgramError::Custom(PriceError::InsufficientHistory as u32)));
+
+        price_history.append(PriceHistoryEntry { price: 100, confidence: 10, timestamp: 1_000, slot: 1 });
+        assert_eq!(price_history.twap(1_060, 61), Err(ProgramError::Custom(PriceError::InsufficientHistory as u32)));
+        assert_eq!(price_history.twap(1_060, 60), Ok((100, 10)));
+        assert_eq!(price_history.twap(1_060, 0), Err(ProgramError::Custom(PriceError::InvalidTwapWindow as u32)));
+    }
+
+    #[test]
+    fn test_verify_feed() {
+        let price_history = PriceHistory { bump: 254, price_account: [7u8; 32], ..Default::default() };
+
+        assert!(price_history.verify_feed(&[7u8; 32], 254).is_ok());
+        assert_eq!(price_history.verify_feed(&[8u8; 32], 254), Err(ProgramError::Custom(PriceError::InvalidPriceHistoryAddress as u32)));
+        assert_eq!(price_history.verify_feed(&[7u8; 32], 253), Err(ProgramError::Custom(PriceError::InvalidPriceHistoryAddress as u32)));
+    }
+}
@@ -0,0 +1,119 @@
+use pinocchio::{account_info::{AccountInfo, Ref, RefMut}, program_error::ProgramError};
+
+use crate::errors::PriceError;
+
+/// Zero-copy access shared by the program's fixed-size accounts, which all start with an
+/// 8-byte discriminator followed by a one-byte version.
+///
+/// # Safety
+///
+/// Implementors must be `repr(C)` with 8-byte alignment, start with `discriminator: [u8; 8]`
+/// and `version: u8`, and contain only integer and byte-array fields so that every bit
+/// pattern is a valid value.
+pub unsafe trait ZeroCopy: Copy + Sized {
+    const DISCRIMINATOR: [u8; 8];
+    const VERSION: u8;
+    const SIZE: usize = core::mem::size_of::<Self>();
+
+    /// Copies a value out of `data`. Sound for any buffer alignment; intended for off-chain
+    /// decoding and tests.
+    fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < Self::SIZE {
+            return Err(PriceError::InvalidAccountSize.into());
+        };
+
+        // SAFETY: `data` holds at least `SIZE` bytes and every bit pattern is valid.
+        Ok(unsafe { core::ptr::read_unaligned(data.as_ptr() as *const Self) })
+    }
+
+    /// Writes `self` into the first `SIZE` bytes of `data`. Sound for any buffer alignment.
+    fn store(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        if data.len() < Self::SIZE {
+            return Err(PriceError::InvalidAccountSize.into());
+        };
+
+        // SAFETY: `data` holds at least `SIZE` writable bytes.
+        unsafe { core::ptr::write_unaligned(data.as_mut_ptr() as *mut Self, *self) };
+
+        Ok(())
+    }
+
+    /// Writes the discriminator and version into a freshly allocated account and returns it
+    /// for initialization of the remaining fields.
+    fn init_header(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        check_owner_and_size::<Self>(account)?;
+
+        let mut data = account.try_borrow_mut_data()?;
+        check_alignment::<Self>(&data)?;
+
+        if data[..8] != [0u8; 8] {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        };
+
+        data[..8].copy_from_slice(&Self::DISCRIMINATOR);
+        data[8] = Self::VERSION;
+
+        // SAFETY: size and alignment were checked above and every bit pattern is valid.
+        Ok(RefMut::map(data, |data| unsafe {
+            &mut *(data.as_mut_ptr() as *mut Self)
+        }))
+    }
+
+    fn from_account_info(account: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        check_owner_and_size::<Self>(account)?;
+
+        let data = account.try_borrow_data()?;
+        check_alignment::<Self>(&data)?;
+        check_header::<Self>(&data)?;
+
+        // SAFETY: size and alignment were checked above and every bit pattern is valid.
+        Ok(Ref::map(data, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    fn from_account_info_mut(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        check_owner_and_size::<Self>(account)?;
+
+        let data = account.try_borrow_mut_data()?;
+        check_alignment::<Self>(&data)?;
+        check_header::<Self>(&data)?;
+
+        // SAFETY: size and alignment were checked above and every bit pattern is valid.
+        Ok(RefMut::map(data, |data| unsafe {
+            &mut *(data.as_mut_ptr() as *mut Self)
+        }))
+    }
+}
+
+fn check_owner_and_size<T: ZeroCopy>(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_owned_by(&crate::ID) {
+        return Err(PriceError::InvalidAccountOwner.into());
+    };
+
+    if account.data_len() < T::SIZE {
+        return Err(PriceError::InvalidAccountSize.into());
+    };
+
+    Ok(())
+}
+
+fn check_alignment<T: ZeroCopy>(data: &[u8]) -> Result<(), ProgramError> {
+    if data.as_ptr().align_offset(core::mem::align_of::<T>()) != 0 {
+        return Err(PriceError::MisalignedAccountData.into());
+    };
+
+    Ok(())
+}
+
+fn check_header<T: ZeroCopy>(data: &[u8]) -> Result<(), ProgramError> {
+    if data[..8] != T::DISCRIMINATOR {
+        return Err(PriceError::InvalidDiscriminator.into());
+    };
+
+    if data[8] != T::VERSION {
+        return Err(PriceError::UnsupportedVersion.into());
+    };
+
+    Ok(())
+}
@@ -0,0 +1,273 @@
+//! Fixtures shared by the instruction tests.
+
+use std::{fs, path::Path, time::SystemTime};
+
+use mollusk_svm::{program, Mollusk};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    seeds::{config_seeds, price_account_seeds, price_account_symbol_seeds, price_history_seeds, program_data_seeds, BPF_LOADER_UPGRADEABLE_ID},
+    states::{Config, PriceAccount, PriceAccountSeeds, PriceHistory, ZeroCopy},
+};
+
+pub const PROGRAM_ID: Pubkey = solana_sdk::pubkey!("4zSrGy87rYtohmWK7PLBsojskZQa38GMwmoQkeK1nJSD");
+pub const AUTHORITY: Pubkey = Pubkey::new_from_array([1u8; 32]);
+pub const PRICE_ACCOUNT_ID: u64 = 13;
+
+const PROGRAM_PATH: &str = "target/deploy/pinocchio_price_account";
+
+/// Loads the program built by `cargo build-sbf`. Panics when the binary is missing or older
+/// than the sources, so the instruction tests never pass or fail against a stale build; run
+/// them with `cargo test-sbf` (or `npm run test:program`), which rebuilds it first.
+pub fn mollusk() -> Mollusk {
+    let program = fs::metadata(format!("{PROGRAM_PATH}.so"))
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or_else(|_| panic!("{PROGRAM_PATH}.so not found; run `cargo build-sbf` first"));
+
+    assert!(
+        program >= last_modified(Path::new("src")),
+        "{PROGRAM_PATH}.so is older than src/; run `cargo build-sbf` first"
+    );
+
+    Mollusk::new(&PROGRAM_ID, PROGRAM_PATH)
+}
+
+fn last_modified(path: &Path) -> SystemTime {
+    let metadata = fs::metadata(path).unwrap();
+
+    if !metadata.is_dir() {
+        return metadata.modified().unwrap();
+    };
+
+    fs::read_dir(path)
+        .unwrap()
+        .map(|entry| last_modified(&entry.unwrap().path()))
+        .max()
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+pub fn system_account(lamports: u64) -> Account {
+    Account {
+        lamports,
+        data: vec![],
+        owner: solana_sdk::system_program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn signer_account() -> Account {
+    system_account(10_000_000)
+}
+
+pub fn program_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000,
+        data,
+        owner: PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn price_account_address(price_account_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&price_account_seeds(&price_account_id.to_le_bytes()), &PROGRAM_ID)
+}
+
+pub fn price_account_symbol_address(symbol: &[u8]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&price_account_symbol_seeds(symbol), &PROGRAM_ID)
+}
+
+pub fn price_history_address(price_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&price_history_seeds(price_account.as_ref()), &PROGRAM_ID)
+}
+
+pub fn config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&config_seeds(), &PROGRAM_ID)
+}
+
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&program_data_seeds(), &Pubkey::new_from_array(BPF_LOADER_UPGRADEABLE_ID)).0
+}
+
+/// An initialized feed at `PRICE_ACCOUNT_ID` owned by `AUTHORITY` that has not published
+/// yet: exponent -8, a 1% confidence limit and no price bounds. Tests override the fields
+/// they exercise with struct update syntax.
+pub fn mock_price_account(bump: u8) -> PriceAccount {
+    let (price_account_pda, _bump) = price_account_address(PRICE_ACCOUNT_ID);
+
+    PriceAccount {
+        discriminator: PriceAccount::DISCRIMINATOR,
+        version: PriceAccount::VERSION,
+        exponent: -8,
+        max_confidence_ratio_bps: 100,
+        authority: AUTHORITY.to_bytes(),
+        price_account_bump: bump,
+        price_history_bump: price_history_address(&price_account_pda).1,
+        min_price: i64::MIN,
+        max_price: i64::MAX,
+        ..Default::default()
+    }
+}
+
+pub fn price_account_data(price_account: &PriceAccount) -> Vec<u8> {
+    let mut data = vec![0u8; PriceAccount::SIZE];
+    price_account.store(&mut data).unwrap();
+    data
+}
+
+pub fn mock_price_account_data(bump: u8) -> Vec<u8> {
+    price_account_data(&mock_price_account(bump))
+}
+
+/// An empty history for the feed at `price_account_pda`.
+pub fn mock_price_history(price_account_pda: &Pubkey) -> (Pubkey, Account) {
+    let (price_history_pda, bump) = price_history_address(price_account_pda);
+
+    let price_history = PriceHistory {
+        discriminator: PriceHistory::DISCRIMINATOR,
+        version: PriceHistory::VERSION,
+        bump,
+        price_account: price_account_pda.to_bytes(),
+        ..Default::default()
+    };
+
+    let mut data = vec![0u8; PriceHistory::SIZE];
+    price_history.store(&mut data).unwrap();
+
+    (price_history_pda, program_account(data))
+}
+
+/// The config singleton with feed defaults of 120s staleness, 500 bps deviation over 10 slots.
+pub fn mock_config(admin: Pubkey, permissionless_creation: u8) -> (Pubkey, Account) {
+    let (config_pda, bump) = config_address();
+
+    let config = Config {
+        discriminator: Config::DISCRIMINATOR,
+        version: Config::VERSION,
+        bump,
+        admin: admin.to_bytes(),
+        permissionless_creation,
+        default_max_staleness_secs: 120,
+        default_max_deviation_bps: 500,
+        default_deviation_window_slots: 10,
+        ..Default::default()
+    };
+
+    let mut data = vec![0u8; Config::SIZE];
+    config.store(&mut data).unwrap();
+
+    (config_pda, program_account(data))
+}
+
+/// `CreatePriceAccount` arguments, defaulting to an id-seeded SOL/USD feed.
+pub struct CreatePriceAccountArgs {
+    pub price_account_id: u64,
+    pub exponent: i32,
+    pub max_confidence_ratio_bps: u16,
+    pub max_staleness_secs: u64,
+    pub min_publishers: u8,
+    pub ema_half_life_slots: u64,
+    pub max_deviation_bps: u16,
+    pub deviation_window_slots: u64,
+    pub allow_negative: u8,
+    pub min_price: i64,
+    pub max_price: i64,
+    pub symbol: [u8; 16],
+    pub base_asset: [u8; 8],
+    pub quote_asset: [u8; 8],
+    pub decimals: u8,
+    pub seed_kind: PriceAccountSeeds,
+}
+
+impl Default for CreatePriceAccountArgs {
+    fn default() -> Self {
+        Self {
+            price_account_id: PRICE_ACCOUNT_ID,
+            exponent: -8,
+            max_confidence_ratio_bps: 100,
+            max_staleness_secs: 60,
+            min_publishers: 1,
+            ema_half_life_slots: 25,
+            max_deviation_bps: 1_000,
+            deviation_window_slots: 150,
+            allow_negative: 0,
+            min_price: i64::MIN,
+            max_price: i64::MAX,
+            symbol: *b"SOL/USD\0\0\0\0\0\0\0\0\0",
+            base_asset: *b"SOL\0\0\0\0\0",
+            quote_asset: *b"USD\0\0\0\0\0",
+            decimals: 9,
+            seed_kind: PriceAccountSeeds::Id,
+        }
+    }
+}
+
+impl CreatePriceAccountArgs {
+    /// The discriminator followed by the 92-byte payload.
+    pub fn instruction_data(&self) -> Vec<u8> {
+        let mut instruction_data = vec![];
+        instruction_data.push(0u8);
+        instruction_data.extend_from_slice(&self.price_account_id.to_le_bytes());
+        instruction_data.extend_from_slice(&self.exponent.to_le_bytes());
+        instruction_data.extend_from_slice(&self.max_confidence_ratio_bps.to_le_bytes());
+        instruction_data.extend_from_slice(&self.max_staleness_secs.to_le_bytes());
+        instruction_data.push(self.min_publishers);
+        instruction_data.extend_from_slice(&self.ema_half_life_slots.to_le_bytes());
+        instruction_data.extend_from_slice(&self.max_deviation_bps.to_le_bytes());
+        instruction_data.extend_from_slice(&self.deviation_window_slots.to_le_bytes());
+        instruction_data.push(self.allow_negative);
+        instruction_data.extend_from_slice(&self.min_price.to_le_bytes());
+        instruction_data.extend_from_slice(&self.max_price.to_le_bytes());
+        instruction_data.extend_from_slice(&self.symbol);
+        instruction_data.extend_from_slice(&self.base_asset);
+        instruction_data.extend_from_slice(&self.quote_asset);
+        instruction_data.push(self.decimals);
+        instruction_data.push(self.seed_kind as u8);
+        instruction_data
+    }
+
+    /// The feed address these arguments derive.
+    pub fn price_account_address(&self) -> Pubkey {
+        match self.seed_kind {
+            PriceAccountSeeds::Id => price_account_address(self.price_account_id).0,
+            PriceAccountSeeds::Symbol => price_account_symbol_address(&self.symbol).0,
+        }
+    }
+
+    /// Creates the derived feed and its history, paid by `signer`, against the config at `config_pda`.
+    pub fn instruction(&self, signer: Pubkey, config_pda: Pubkey) -> Instruction {
+        let price_account_pda = self.price_account_address();
+        let (price_history_pda, _) = price_history_address(&price_account_pda);
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(signer, true),
+                AccountMeta::new(price_account_pda, false),
+                AccountMeta::new(solana_sdk::system_program::id(), false),
+                AccountMeta::new(price_history_pda, false),
+                AccountMeta::new_readonly(config_pda, false),
+            ],
+            data: self.instruction_data(),
+        }
+    }
+
+    /// The accounts `instruction` expects before the feed exists, with a funded `signer`.
+    pub fn accounts(&self, signer: Pubkey, config: (Pubkey, Account)) -> Vec<(Pubkey, Account)> {
+        let price_account_pda = self.price_account_address();
+        let (price_history_pda, _) = price_history_address(&price_account_pda);
+
+        vec![
+            (signer, system_account(100_000_000)),
+            (price_account_pda, Account::default()),
+            program::keyed_account_for_system_program(),
+            (price_history_pda, Account::default()),
+            config,
+        ]
+    }
+}
@@ -32,6 +32,59 @@ function createSetComputeUnitLimitInstruction(units: number): TransactionInstruc
     });
 }
 
+// PDA seed prefixes, mirroring `src/seeds.rs`.
+const PRICE_ACCOUNT_SEED = Buffer.from("price_feed_account");
+const PRICE_ACCOUNT_SYMBOL_SEED = Buffer.from("price_feed");
+const PRICE_HISTORY_SEED = Buffer.from("price_history");
+const CONFIG_SEED = Buffer.from("config");
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+// Zero-padded ASCII, as stored in `symbol`, `base_asset` and `quote_asset`.
+function encodeFixedString(value: string, len: number): Buffer {
+    const buffer = Buffer.alloc(len);
+    buffer.write(value, "ascii");
+    return buffer;
+}
+
+function decodeFixedString(data: Buffer): string {
+    return data.toString("ascii").replace(/\0+$/, "");
+}
+
+// Byte offsets follow the `repr(C)` layout documented on `PriceAccount`.
+function decodePriceAccount(data: Buffer) {
+    return {
+        discriminator: data.subarray(0, 8).toString(),
+        version: data.readUInt8(8),
+        status: data.readUInt8(9),
+        priceAccountBump: data.readUInt8(10),
+        price: data.readBigInt64LE(16).toString(),
+        confidence: data.readBigUInt64LE(24).toString(),
+        exponent: data.readInt32LE(32),
+        maxConfidenceRatioBps: data.readUInt16LE(36),
+        lastUpdatedTimestamp: data.readBigInt64LE(40).toString(),
+        authority: new PublicKey(data.subarray(48, 80)).toString(),
+        pendingAuthority: new PublicKey(data.subarray(80, 112)).toString(),
+        maxStalenessSecs: data.readBigUInt64LE(112).toString(),
+        lastUpdatedSlot: data.readBigUInt64LE(120).toString(),
+        numPublishers: data.readUInt8(128),
+        minPublishers: data.readUInt8(129),
+        emaPrice: data.readBigInt64LE(584).toString(),
+        emaConfidence: data.readBigUInt64LE(592).toString(),
+        emaHalfLifeSlots: data.readBigUInt64LE(600).toString(),
+        maxDeviationBps: data.readUInt16LE(38),
+        deviationWindowSlots: data.readBigUInt64LE(608).toString(),
+        allowNegative: data.readUInt8(12) !== 0,
+        minPrice: data.readBigInt64LE(616).toString(),
+        maxPrice: data.readBigInt64LE(624).toString(),
+        symbol: decodeFixedString(data.subarray(632, 648)),
+        baseAsset: decodeFixedString(data.subarray(648, 656)),
+        quoteAsset: decodeFixedString(data.subarray(656, 664)),
+        decimals: data.readUInt8(664),
+        lastPublishSlot: data.readBigUInt64LE(672).toString(),
+        seedKind: data.readUInt8(13),
+    };
+}
+
 describe('Create Price Account', function() {
     this.timeout(10000);
     
@@ -40,7 +93,31 @@ describe('Create Price Account', function() {
     let provider: AnchorProvider;
     
     const priceAccountId = new BN(838);
+    const priceExponent = -8;
+    const maxConfidenceRatioBps = 100;
+    const maxStalenessSecs = BigInt(60);
+    const minPublishers = 1;
+    const emaHalfLifeSlots = BigInt(25);
+    const maxDeviationBps = 1_000;
+    const deviationWindowSlots = BigInt(150);
+    const allowNegative = 0;
+    // Unbounded: i64::MIN and i64::MAX.
+    const minPrice = -(BigInt(2) ** BigInt(63));
+    const maxPrice = BigInt(2) ** BigInt(63) - BigInt(1);
+    const symbol = "SOL/USD";
+    const baseAsset = "SOL";
+    const quoteAsset = "USD";
+    const decimals = 9;
+    // 0 derives the feed address from `priceAccountId`, 1 from `symbol` via PRICE_ACCOUNT_SYMBOL_SEED.
+    const seedKind = 0;
     let priceAccountPda: PublicKey;
+    let priceHistoryPda: PublicKey;
+    let configPda: PublicKey;
+    let programDataPda: PublicKey;
+
+    const priceAccountSeeds = (): Buffer[] => seedKind === 1
+        ? [PRICE_ACCOUNT_SYMBOL_SEED, Buffer.from(symbol)]
+        : [PRICE_ACCOUNT_SEED, priceAccountId.toArrayLike(Buffer, "le", 8)];
 
     before(async function () {
         connection = new Connection('https://api.devnet.solana.com', 'confirmed');
@@ -88,15 +165,56 @@ describe('Create Price Account', function() {
         console.log("Accounts : ", program.account);
         
         [priceAccountPda] = PublicKey.findProgramAddressSync(
-            [Buffer.from("price_feed_account"), priceAccountId.toArrayLike(Buffer, "le", 8)],
+            priceAccountSeeds(),
             program.programId
         );
         console.log("Price Account PDA:", priceAccountPda.toString());
+
+        [priceHistoryPda] = PublicKey.findProgramAddressSync(
+            [PRICE_HISTORY_SEED, priceAccountPda.toBuffer()],
+            program.programId
+        );
+        console.log("Price History PDA:", priceHistoryPda.toString());
+
+        [configPda] = PublicKey.findProgramAddressSync([CONFIG_SEED], program.programId);
+        console.log("Config PDA:", configPda.toString());
+
+        [programDataPda] = PublicKey.findProgramAddressSync([program.programId.toBuffer()], BPF_LOADER_UPGRADEABLE_ID);
+    });
+
+    it("Initialize Config", async () => {
+        // The config is a program-wide singleton, so it only needs initializing once per deployment,
+        // by the program's upgrade authority (the wallet that deployed it).
+        if (await connection.getAccountInfo(configPda)) {
+            console.log("Config already initialized");
+            return;
+        }
+
+        const instructionDiscriminant = Buffer.from([15]); // Per the IDL
+        const configData = Buffer.alloc(19);
+        configData.writeUInt8(0, 0); // admin-only feed creation
+        configData.writeBigUInt64LE(maxStalenessSecs, 1);
+        configData.writeUInt16LE(maxDeviationBps, 9);
+        configData.writeBigUInt64LE(deviationWindowSlots, 11);
+
+        const ix = new TransactionInstruction({
+            programId: program.programId,
+            keys: [
+                { pubkey: provider.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: configPda, isSigner: false, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+                { pubkey: programDataPda, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([instructionDiscriminant, configData]),
+        });
+
+        const sig = await provider.sendAndConfirm(new Transaction().add(ix), []);
+        console.log("Transaction Signature:", sig);
     });
 
     it("Should create Price Account", async () => {
         const [priceAccountPda, bump] = PublicKey.findProgramAddressSync(
-            [Buffer.from("price_feed_account"), priceAccountId.toArrayLike(Buffer, "le", 8)],
+            priceAccountSeeds(),
             program.programId
         );
 
@@ -104,7 +222,25 @@ describe('Create Price Account', function() {
         
         const instructionDiscriminant = Buffer.from([0]); // Per the IDL
         const priceAccountIdBuffer = priceAccountId.toArrayLike(Buffer, "le", 8);
-        const instructionData = Buffer.concat([instructionDiscriminant, priceAccountIdBuffer]);
+        const configBuffer = Buffer.alloc(50);
+        configBuffer.writeInt32LE(priceExponent, 0);
+        configBuffer.writeUInt16LE(maxConfidenceRatioBps, 4);
+        configBuffer.writeBigUInt64LE(maxStalenessSecs, 6);
+        configBuffer.writeUInt8(minPublishers, 14);
+        configBuffer.writeBigUInt64LE(emaHalfLifeSlots, 15);
+        configBuffer.writeUInt16LE(maxDeviationBps, 23);
+        configBuffer.writeBigUInt64LE(deviationWindowSlots, 25);
+        configBuffer.writeUInt8(allowNegative, 33);
+        configBuffer.writeBigInt64LE(minPrice, 34);
+        configBuffer.writeBigInt64LE(maxPrice, 42);
+        const metadataBuffer = Buffer.concat([
+            encodeFixedString(symbol, 16),
+            encodeFixedString(baseAsset, 8),
+            encodeFixedString(quoteAsset, 8),
+            Buffer.from([decimals]),
+            Buffer.from([seedKind]),
+        ]);
+        const instructionData = Buffer.concat([instructionDiscriminant, priceAccountIdBuffer, configBuffer, metadataBuffer]);
 
         const ix = new TransactionInstruction({
             programId: program.programId,
@@ -112,6 +248,8 @@ describe('Create Price Account', function() {
                 { pubkey: provider.wallet.publicKey, isSigner: true, isWritable: true },
                 { pubkey: priceAccountPda, isSigner: false, isWritable: true },
                 { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+                { pubkey: priceHistoryPda, isSigner: false, isWritable: true },
+                { pubkey: configPda, isSigner: false, isWritable: false },
             ],
             data: instructionData,
         });
@@ -129,29 +267,24 @@ describe('Create Price Account', function() {
 
         const accountData = accountInfo.data;
 
-        const price = accountData.readDoubleLE(0); 
-        const timestamp = accountData.readBigInt64LE(8); 
-        const bumpFromAccount = accountData.readUInt8(16);
-
-        console.log("Account data:", {
-            price,
-            lastUpdatedTimestamp: timestamp.toString(),
-            priceAccountBump: bumpFromAccount,
-        });
+        console.log("Account data:", decodePriceAccount(accountData));
     });
 
     it("Set Price", async () => {
         const [priceAccountPda, bump] = PublicKey.findProgramAddressSync(
-            [Buffer.from("price_feed_account"), priceAccountId.toArrayLike(Buffer, "le", 8)],
+            priceAccountSeeds(),
             program.programId
         );
 
         const instructionDiscriminant = Buffer.from([1]); // Per the IDL
         const priceAccountIdBuffer = priceAccountId.toArrayLike(Buffer, "le", 8);
-        const priceToSet = 100;
+        const priceToSet = BigInt(100) * BigInt(10 ** 8);
 
-        const priceBuffer = Buffer.allocUnsafe(8);
-        priceBuffer.writeDoubleLE(priceToSet, 0);
+        const priceBuffer = Buffer.alloc(28);
+        priceBuffer.writeBigInt64LE(priceToSet, 0);
+        priceBuffer.writeBigUInt64LE(priceToSet / BigInt(1000), 8);
+        priceBuffer.writeInt32LE(priceExponent, 16);
+        priceBuffer.writeBigUInt64LE(BigInt(await connection.getSlot()), 20);
 
         const instructionData = Buffer.concat([instructionDiscriminant, priceAccountIdBuffer, priceBuffer]);
 
@@ -161,6 +294,7 @@ describe('Create Price Account', function() {
                 { pubkey: provider.wallet.publicKey, isSigner: true, isWritable: true },
                 { pubkey: priceAccountPda, isSigner: false, isWritable: true },
                 { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+                { pubkey: priceHistoryPda, isSigner: false, isWritable: true },
             ],
             data: instructionData,
         });
@@ -178,20 +312,12 @@ describe('Create Price Account', function() {
 
          const accountData = accountInfo.data;
 
-        const price = accountData.readDoubleLE(0); 
-        const timestamp = accountData.readBigInt64LE(8); 
-        const bumpFromAccount = accountData.readUInt8(16);
-
-        console.log("Account data:", {
-            price,
-            lastUpdatedTimestamp: timestamp.toString(),
-            priceAccountBump: bumpFromAccount,
-        });
+        console.log("Account data:", decodePriceAccount(accountData));
     });
 
     it("Modify Price", async () => {
         const [priceAccountPda, bump] = PublicKey.findProgramAddressSync(
-            [Buffer.from("price_feed_account"), priceAccountId.toArrayLike(Buffer, "le", 8)],
+            priceAccountSeeds(),
             program.programId
         );
 
@@ -199,10 +325,17 @@ describe('Create Price Account', function() {
 
         const instructionDiscriminant = Buffer.from([2]);
         const priceAccountIdBuffer = priceAccountId.toArrayLike(Buffer, "le", 8);
-        const modifiedPrice = 140;
-
-        const priceBuffer = Buffer.allocUnsafe(8);
-        priceBuffer.writeDoubleLE(modifiedPrice, 0);
+        // Delta adjustment: moves the price set above from 100 to 105, within the
+        // feed's `maxDeviationBps` circuit breaker.
+        const delta = BigInt(5) * BigInt(10 ** 8);
+        const modifiedPrice = BigInt(105) * BigInt(10 ** 8);
+
+        const priceBuffer = Buffer.alloc(29);
+        priceBuffer.writeUInt8(0, 0);
+        priceBuffer.writeBigInt64LE(delta, 1);
+        priceBuffer.writeBigUInt64LE(modifiedPrice / BigInt(1000), 9);
+        priceBuffer.writeInt32LE(priceExponent, 17);
+        priceBuffer.writeBigUInt64LE(BigInt(await connection.getSlot()), 21);
 
         const instructionData = Buffer.concat([instructionDiscriminant, priceAccountIdBuffer, priceBuffer]);
 
@@ -212,6 +345,7 @@ describe('Create Price Account', function() {
                 { pubkey: provider.wallet.publicKey, isSigner: true, isWritable: true },
                 { pubkey: priceAccountPda, isSigner: false, isWritable: true },
                 { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+                { pubkey: priceHistoryPda, isSigner: false, isWritable: true },
             ],
             data: instructionData,
         });
@@ -239,14 +373,6 @@ describe('Create Price Account', function() {
 
          const accountData = accountInfo.data;
 
-        const price = accountData.readDoubleLE(0); 
-        const timestamp = accountData.readBigInt64LE(8); 
-        const bumpFromAccount = accountData.readUInt8(16);
-
-        console.log("Account data:", {
-            price,
-            lastUpdatedTimestamp: timestamp.toString(),
-            priceAccountBump: bumpFromAccount,
-        });
+        console.log("Account data:", decodePriceAccount(accountData));
     })
 });
\ No newline at end of file